@@ -0,0 +1,269 @@
+use std::fmt;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::day1;
+use crate::loader::Input;
+use crate::parse_error::ParseError;
+use crate::runner;
+use crate::solution::{AnySolution, Answer, Part, Solution};
+
+/// How often every step is executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// runs which are thrown away (caches, allocator, ...)
+    pub warmup: usize,
+    /// runs which are actually measured
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self { warmup: 3, samples: 10 }
+    }
+}
+
+/// The measured durations of one step, sorted ascending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    samples: Vec<Duration>,
+}
+
+impl Stats {
+    /// `samples` must not be empty
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "need at least one sample");
+        samples.sort();
+        Self { samples }
+    }
+
+    /// The middle sample; for an even number of samples the mean of the two middle ones
+    pub fn median(&self) -> Duration {
+        let n = self.samples.len();
+        if n % 2 == 1 {
+            return self.samples[n / 2];
+        }
+        (self.samples[n / 2 - 1] + self.samples[n / 2]) / 2
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples[0]
+    }
+
+    pub fn max(&self) -> Duration {
+        self.samples[self.samples.len() - 1]
+    }
+
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+}
+
+/// Runs `f` `config.warmup` times without looking and then `config.samples` times measured.
+/// The results are passed through [black_box] so the compiler can't drop the work
+pub fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+
+    let samples = (0..config.samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(samples)
+}
+
+/// Like [measure] but a panic inside `f` is reported as an error
+fn measure_caught<T>(config: &BenchConfig, f: impl FnMut() -> T) -> Result<Stats, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| measure(config, f)))
+        .map_err(runner::panic_message)
+}
+
+/// Like [measure_caught] but an `Err` of the first call is reported as well (nothing gets measured then)
+fn measure_fallible<T, E: fmt::Display>(config: &BenchConfig, mut f: impl FnMut() -> Result<T, E>) -> Result<Stats, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| f().map_err(|err| err.to_string())))
+        .map_err(runner::panic_message)??;
+    measure_caught(config, f)
+}
+
+/// Timings of a single day: parsing and each requested part
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: Result<Stats, String>,
+    pub parts: Vec<(Part, Result<Stats, String>)>,
+}
+
+impl DayBench {
+    pub fn is_ok(&self) -> bool {
+        self.parse.is_ok() && self.parts.iter().all(|(_, stats)| stats.is_ok())
+    }
+}
+
+/// Benchmarks parsing and every part in `parts` of `solution` on `data`.
+/// The parts always work on an input which was parsed once beforehand,
+/// so their timings don't contain the parsing
+pub fn bench_day(solution: &dyn AnySolution, parts: &[Part], data: &Input, config: &BenchConfig) -> DayBench {
+    let input = match solution.parse_any(data.lines()) {
+        Ok(input) => input,
+        Err(err) => return DayBench {
+            day: solution.day(),
+            parse: Err(err.to_string()),
+            parts: parts.iter().map(|part| (*part, Err(err.to_string()))).collect(),
+        },
+    };
+
+    DayBench {
+        day: solution.day(),
+        parse: measure_caught(config, || solution.parse_any(data.lines())),
+        parts: parts
+            .iter()
+            .map(|part| (*part, measure_fallible(config, || solution.solve(input.as_ref(), *part))))
+            .collect(),
+    }
+}
+
+/// An alternative implementation of one part working on the regular parsed input
+#[derive(Debug)]
+pub struct Variant<I> {
+    pub name: &'static str,
+    pub part: Part,
+    pub run: fn(&I) -> Answer,
+}
+
+/// Timings (and the answer, so we can see they agree) of one [Variant]
+#[derive(Debug)]
+pub struct VariantBench {
+    pub day: u8,
+    pub name: &'static str,
+    pub part: Part,
+    pub answer: Result<Answer, String>,
+    pub stats: Result<Stats, String>,
+}
+
+/// Parses `lines` once and benchmarks all `variants` of `parts` on the result
+pub fn compare<S>(solution: &S, variants: &[Variant<S::Input>], parts: &[Part], lines: &[String], config: &BenchConfig)
+    -> Result<Vec<VariantBench>, ParseError> where S: Solution {
+    let input = solution.parse(lines)?;
+
+    Ok(variants
+        .iter()
+        .filter(|variant| parts.contains(&variant.part))
+        .map(|variant| VariantBench {
+            day: solution.day(),
+            name: variant.name,
+            part: variant.part,
+            answer: panic::catch_unwind(AssertUnwindSafe(|| (variant.run)(&input)))
+                .map_err(runner::panic_message),
+            stats: measure_caught(config, || (variant.run)(&input)),
+        })
+        .collect())
+}
+
+/// Compares the alternative implementations of `day`, `None` if the day has none
+pub fn compare_day(day: u8, parts: &[Part], lines: &[String], config: &BenchConfig)
+    -> Option<Result<Vec<VariantBench>, ParseError>> {
+    match day {
+        1 => Some(compare(&day1::Day1, &day1::VARIANTS, parts, lines, config)),
+        _ => None,
+    }
+}
+
+fn print_stats_row(day: u8, step: &str, stats: &Result<Stats, String>) {
+    match stats {
+        Ok(stats) => println!("{:>4} | {:<6} | {:>12} | {:>12} | {:>12}",
+                              day,
+                              step,
+                              format!("{:.3?}", stats.median()),
+                              format!("{:.3?}", stats.min()),
+                              format!("{:.3?}", stats.max())),
+        Err(msg) => println!("{:>4} | {:<6} | FAILED: {}", day, step, msg),
+    }
+}
+
+/// Prints median / min / max of every step of every day
+pub fn print_report(benches: &[DayBench]) {
+    println!("{:>4} | {:<6} | {:>12} | {:>12} | {:>12}", "Day", "Step", "Median", "Min", "Max");
+    println!("{}", "-".repeat(58));
+    for bench in benches {
+        print_stats_row(bench.day, "parse", &bench.parse);
+        for (part, stats) in &bench.parts {
+            print_stats_row(bench.day, &format!("part {}", part), stats);
+        }
+    }
+}
+
+/// Prints the variants of each part next to each other
+pub fn print_comparison(variants: &[VariantBench]) {
+    println!("{:>4} | {:>4} | {:<20} | {:<14} | {:>12} | {:>12} | {:>12}",
+             "Day", "Part", "Variant", "Answer", "Median", "Min", "Max");
+    println!("{}", "-".repeat(95));
+    for variant in variants {
+        let answer = match &variant.answer {
+            Ok(answer) => answer.to_string(),
+            Err(msg) => format!("FAILED: {}", msg),
+        };
+        match &variant.stats {
+            Ok(stats) => println!("{:>4} | {:>4} | {:<20} | {:<14} | {:>12} | {:>12} | {:>12}",
+                                  variant.day,
+                                  variant.part,
+                                  variant.name,
+                                  answer,
+                                  format!("{:.3?}", stats.median()),
+                                  format!("{:.3?}", stats.min()),
+                                  format!("{:.3?}", stats.max())),
+            Err(msg) => println!("{:>4} | {:>4} | {:<20} | FAILED: {}", variant.day, variant.part, variant.name, msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[test]
+    fn stats_of_an_odd_number_of_samples() {
+        let stats = Stats::from_samples(millis(&[5, 1, 3]));
+        assert_eq!(stats.median(), Duration::from_millis(3));
+        assert_eq!(stats.min(), Duration::from_millis(1));
+        assert_eq!(stats.max(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn median_of_an_even_number_of_samples_is_the_mean_of_the_middle() {
+        let stats = Stats::from_samples(millis(&[4, 1, 2, 8]));
+        assert_eq!(stats.median(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn measure_takes_the_requested_number_of_samples() {
+        let mut calls = 0;
+        let stats = measure(&BenchConfig { warmup: 2, samples: 5 }, || calls += 1);
+        assert_eq!(calls, 7);
+        assert_eq!(stats.samples().len(), 5);
+    }
+
+    #[test]
+    fn day1_variants_agree() {
+        let lines: Vec<String> = ["199", "200", "208", "210", "200", "207", "240", "269", "260", "263"]
+            .iter()
+            .map(|line| String::from(*line))
+            .collect();
+        let config = BenchConfig { warmup: 0, samples: 1 };
+        let variants = compare_day(1, &[Part::Two], &lines, &config).unwrap().unwrap();
+
+        assert_eq!(variants.len(), 4);
+        for variant in variants {
+            assert_eq!(variant.answer, Ok(Answer::Integer(5)), "{}", variant.name);
+        }
+    }
+}
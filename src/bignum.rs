@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Something to count with: fixed size integers (which report overflows) or [BigUint] (which never overflows)
+pub trait Count: Clone + Ord + fmt::Debug + fmt::Display {
+    fn zero() -> Self;
+
+    fn from_u64(value: u64) -> Self;
+
+    /// `None` if the sum doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// `self - other`, `other` must not be larger than `self`
+    fn minus(&self, other: &Self) -> Self;
+}
+
+impl Count for u64 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn from_u64(value: u64) -> Self {
+        value
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self - other
+    }
+}
+
+impl Count for u128 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn from_u64(value: u64) -> Self {
+        value as u128
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u128::checked_add(*self, *other)
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self - other
+    }
+}
+
+/// An unsigned integer of any size. It only knows what counting needs: adding, subtracting, comparing and printing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// least significant first, never ends with a 0 (zero has no limbs at all)
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+
+        let mut carry = 0u128;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u128 + *other.limbs.get(i).unwrap_or(&0) as u128 + carry;
+            *limb = sum as u64;
+            carry = sum >> 64;
+            if carry == 0 && i >= other.limbs.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u64);
+        }
+    }
+
+    /// `None` if `other` is larger
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut result = self.clone();
+        let mut borrow = false;
+        for (i, limb) in result.limbs.iter_mut().enumerate() {
+            let (diff, borrow_1) = limb.overflowing_sub(*other.limbs.get(i).unwrap_or(&0));
+            let (diff, borrow_2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = borrow_1 || borrow_2;
+        }
+        result.trim();
+        Some(result)
+    }
+
+    /// The value if it fits into a `u128`
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [low] => Some(*low as u128),
+            [low, high] => Some((*high as u128) << 64 | *low as u128),
+            _ => None,
+        }
+    }
+
+    /// Divides in place by `divisor` and returns the remainder
+    fn div_rem_small(&mut self, divisor: u64) -> u64 {
+        let mut remainder = 0u128;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = remainder << 64 | *limb as u128;
+            *limb = (current / divisor as u128) as u64;
+            remainder = current % divisor as u128;
+        }
+        self.trim();
+        remainder as u64
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut number = Self { limbs: vec![value] };
+        number.trim();
+        number
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        let mut number = Self { limbs: vec![value as u64, (value >> 64) as u64] };
+        number.trim();
+        number
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // split into chunks of 19 decimal digits, the most a u64 can hold
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(CHUNK));
+        }
+
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{:019}", chunk);
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl Count for BigUint {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from(value)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        let mut sum = self.clone();
+        sum.add_assign(other);
+        Some(sum)
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        self.checked_sub(other).expect("subtrahend larger than minuend")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_and_subtracts_across_limbs() {
+        let max = BigUint::from(u64::MAX);
+        let mut sum = max.clone();
+        sum.add_assign(&BigUint::from(1u64));
+        assert_eq!(sum.to_u128(), Some(u64::MAX as u128 + 1));
+        assert_eq!(sum.checked_sub(&max), Some(BigUint::from(1u64)));
+        assert_eq!(max.checked_sub(&sum), None);
+        assert_eq!(sum.minus(&sum), BigUint::zero());
+        assert!(BigUint::zero().is_zero());
+
+        let big = BigUint::from(u128::MAX);
+        assert!(big > sum && sum > max);
+        assert_eq!(big.checked_add(&BigUint::from(1u64)).unwrap().to_u128(), None);
+    }
+
+    #[test]
+    fn prints_in_decimal() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(format!("{:>5}", BigUint::from(42u64)), "   42");
+
+        // 2^200
+        let mut power = BigUint::from(1u64);
+        for _ in 0..200 {
+            let copy = power.clone();
+            power.add_assign(&copy);
+        }
+        assert_eq!(power.to_string(), "1606938044258990275541962092341162602522202993782792835301376");
+    }
+
+    #[test]
+    fn fixed_size_counts_report_overflows() {
+        assert_eq!(Count::checked_add(&u64::MAX, &1), None);
+        assert_eq!(Count::checked_add(&(u64::MAX as u128), &1), Some(1 << 64));
+        assert_eq!(<u128 as Count>::from_u64(7).minus(&2), 5);
+    }
+}
@@ -0,0 +1,1035 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::bench::{self, BenchConfig};
+use crate::config::{self, Config};
+use crate::day1::{Sweep, SweepError};
+use crate::examples;
+use crate::expected::Dataset;
+use crate::fetch::{Fetched, Fetcher, Resource};
+use crate::log::{self, error, info, warning, Level};
+use crate::memory;
+use crate::mock_server;
+use crate::output::{self, OutputFormat};
+use crate::registry;
+use crate::runner::{self, InputKind, PoolConfig};
+use crate::scaffold;
+use crate::solution::{Answer, AnySolution, Part};
+use crate::submit::{self, Outcome};
+use crate::verify;
+use crate::watch::{self, WatchConfig};
+
+const USAGE: &str = "\
+Usage:
+    aoc [-q | -v | -vv] <command> ...
+
+    aoc run --day <N> [--part <1|2>] [--input <test|real|PATH|->] [--no-trim] [--format <table|json|csv>]
+    aoc run --all [--part <1|2>] [--input <test|real>] [--no-trim] [--format <table|json|csv>]
+            [--jobs <N>] [--timeout <SECONDS>] [--memory]
+    aoc watch --day <N> [--part <1|2>] [--input <test|real|PATH>] [--interval <MS>] [--release]
+    aoc sweep [--input <test|real|PATH|->] [--window <N>] [--every <N>]
+    aoc verify [--day <N>] [--part <1|2>] [--input <test|real>] [--record]
+    aoc bench [--day <N>] [--part <1|2>] [--input <test|real|PATH>] [--warmup <N>] [--samples <N>] [--compare]
+    aoc new-day <N> [--title <TITLE>]
+    aoc examples [--day <N>] [--write]
+    aoc fetch --day <N> [--only <task|input>] [--config <PATH>] [--base-url <URL>]
+    aoc submit --day <N> --part <1|2> [--answer <ANSWER>] [--config <PATH>] [--base-url <URL>]
+    aoc mock-server [--port <N>] [--dir <DIR>]
+
+Options:
+    -q, --quiet     only log errors
+    -v, --verbose   log what is executed and intermediate results, twice (-vv, -v -v) to dump data structures
+    --day <N>       run a single day
+    --all           run every registered day
+    --part <1|2>    run only one part (default: both)
+    --input <KIND>  `test` (testinput.txt), `real` (input.txt, default), a path to a file
+                    or `-` to read from stdin
+    --no-trim       keep leading and trailing whitespace of each input line
+    --format <FMT>  (run) `table` (default), `json` (one object per line) or `csv`
+    --jobs <N>      (run) how many days run in parallel (default: one per CPU)
+    --timeout <SECONDS> (run) report a part taking longer as timed out and go on with the others
+    --memory        (run) also report peak heap, allocated bytes and number of allocations of each part
+    --interval <MS> (watch) how often src/dayXY/ is checked for changes (default: 500)
+    --release       (watch) build with optimizations
+    --window <N>    (sweep) window size for counting depth increases like day 1 (default: 3)
+    --every <N>     (sweep) also print the count so far after every N readings
+    --record        (verify) write answers which are not recorded yet to src/dayXY/answers.txt
+    --write         (examples) fill a missing testinput.txt with the example of task.txt
+                    and record the example answers it states
+    --warmup <N>    (bench) unmeasured runs before sampling (default: 3)
+    --samples <N>   (bench) measured runs per step (default: 10)
+    --compare       (bench) also time the alternative implementations of a day (e.g. day 1)
+    --title <TITLE> (new-day) the riddle's title
+    --only <WHAT>   (fetch) only download the description (`task`) or the personal `input`
+    --answer <ANSWER> (submit) submit this instead of what the solution computes for input.txt
+    --config <PATH> (fetch, submit) the config file with the session token (default: aoc.conf)
+    --base-url <URL> (fetch, submit) ask this server instead of the configured one (e.g. the mock server)
+    --port <N>      (mock-server) port to listen on (default: 8080)
+    --dir <DIR>     (mock-server) serve the files below DIR, e.g. DIR/2021/day/1/index.html
+                    and DIR/2021/day/1/input (default: mock)";
+
+/// Everything `aoc run` needs to know
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    /// `None` means all days
+    pub day: Option<u8>,
+    pub parts: Vec<Part>,
+    pub input: InputKind,
+    /// trim whitespace around every input line
+    pub trim: bool,
+    pub format: OutputFormat,
+    pub pool: PoolConfig,
+    /// profile heap allocations
+    pub memory: bool,
+}
+
+/// Everything `aoc sweep` needs to know
+#[derive(Debug, PartialEq)]
+pub struct SweepArgs {
+    /// stdin unless told otherwise
+    pub input: InputKind,
+    pub window: usize,
+    /// print progress every that many readings
+    pub every: Option<usize>,
+}
+
+/// Everything `aoc verify` needs to know
+#[derive(Debug, PartialEq)]
+pub struct VerifyArgs {
+    /// `None` means all days
+    pub day: Option<u8>,
+    pub parts: Vec<Part>,
+    pub datasets: Vec<Dataset>,
+    pub record: bool,
+}
+
+/// Everything `aoc bench` needs to know
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    /// `None` means all days
+    pub day: Option<u8>,
+    pub parts: Vec<Part>,
+    pub input: InputKind,
+    pub config: BenchConfig,
+    /// also benchmark the alternative implementations
+    pub compare: bool,
+}
+
+/// Everything `aoc new-day` needs to know
+#[derive(Debug, PartialEq)]
+pub struct NewDayArgs {
+    pub day: u8,
+    pub title: String,
+}
+
+/// Everything `aoc examples` needs to know
+#[derive(Debug, PartialEq)]
+pub struct ExamplesArgs {
+    /// `None` means all days
+    pub day: Option<u8>,
+    pub write: bool,
+}
+
+/// Everything `aoc fetch` needs to know
+#[derive(Debug, PartialEq)]
+pub struct FetchArgs {
+    pub day: u8,
+    pub resources: Vec<Resource>,
+    pub config: PathBuf,
+    /// overrides the configured base URL
+    pub base_url: Option<String>,
+}
+
+/// Everything `aoc submit` needs to know
+#[derive(Debug, PartialEq)]
+pub struct SubmitArgs {
+    pub day: u8,
+    pub part: Part,
+    /// `None` means: compute it from the real input
+    pub answer: Option<String>,
+    pub config: PathBuf,
+    /// overrides the configured base URL
+    pub base_url: Option<String>,
+}
+
+/// Everything `aoc mock-server` needs to know
+#[derive(Debug, PartialEq)]
+pub struct MockServerArgs {
+    pub port: u16,
+    pub dir: PathBuf,
+}
+
+/// Everything `aoc watch` needs to know
+pub type WatchArgs = WatchConfig;
+
+/// Sub commands the binary understands
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Watch(WatchArgs),
+    Sweep(SweepArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    NewDay(NewDayArgs),
+    Examples(ExamplesArgs),
+    Fetch(FetchArgs),
+    Submit(SubmitArgs),
+    MockServer(MockServerArgs),
+    Help,
+}
+
+/// Fetches the value following an option like `--day`
+fn option_value<'a>(option: &str, args: &mut impl Iterator<Item = &'a String>) -> Result<&'a String, String> {
+    args.next().ok_or(format!("{} expects a value", option))
+}
+
+/// Parses the value of `--day`
+fn parse_day(value: &str) -> Result<u8, String> {
+    value.parse::<u8>().map_err(|_| format!("Invalid day {}", value))
+}
+
+/// Parses the value of `--part`
+fn parse_part(value: &str) -> Result<Part, String> {
+    let part = value.parse::<u8>().ok().and_then(Part::from_number);
+    part.ok_or(format!("Invalid part {} (expected 1 or 2)", value))
+}
+
+fn parse_run(args: &[String]) -> Result<RunArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut all = false;
+    let mut parts = Part::BOTH.to_vec();
+    let mut input = InputKind::Real;
+    let mut trim = true;
+    let mut format = OutputFormat::Table;
+    let mut pool = PoolConfig::default();
+    let mut memory = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--all" | "-a" => all = true,
+            "--part" | "-p" => {
+                parts = vec![parse_part(option_value(arg, &mut args)?)?];
+            },
+            "--input" | "-i" => input = InputKind::from_arg(option_value(arg, &mut args)?),
+            "--no-trim" => trim = false,
+            "--format" | "-f" => {
+                let value = option_value(arg, &mut args)?;
+                format = OutputFormat::from_name(value)
+                    .ok_or(format!("Unknown format {} (expected table, json or csv)", value))?;
+            },
+            "--jobs" | "-j" => {
+                pool.workers = parse_count(arg, option_value(arg, &mut args)?)?;
+                if pool.workers == 0 {
+                    return Err(String::from("--jobs needs at least one worker"));
+                }
+            },
+            "--timeout" => {
+                let value = option_value(arg, &mut args)?;
+                let seconds = value.parse::<f64>().ok().filter(|seconds| *seconds > 0.0 && seconds.is_finite())
+                    .ok_or(format!("--timeout expects a positive number of seconds, not {}", value))?;
+                pool.timeout = Some(Duration::from_secs_f64(seconds));
+            },
+            "--memory" => memory = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    if all == day.is_some() {
+        return Err(String::from("Expected exactly one of --day or --all"));
+    }
+
+    if all && matches!(input, InputKind::Path(_) | InputKind::Stdin) {
+        return Err(String::from("--all only works with --input test or --input real"));
+    }
+
+    Ok(RunArgs { day, parts, input, trim, format, pool, memory })
+}
+
+fn parse_watch(args: &[String]) -> Result<WatchArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut input = InputKind::Real;
+    let mut interval = Duration::from_millis(500);
+    let mut release = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--part" | "-p" => {
+                parts = vec![parse_part(option_value(arg, &mut args)?)?];
+            },
+            "--input" | "-i" => {
+                input = InputKind::from_arg(option_value(arg, &mut args)?);
+                if input == InputKind::Stdin {
+                    return Err(String::from("watch can't read stdin again and again"));
+                }
+            },
+            "--interval" => {
+                let millis = parse_count(arg, option_value(arg, &mut args)?)?;
+                interval = Duration::from_millis(millis.max(1) as u64);
+            },
+            "--release" => release = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    let day = day.ok_or(String::from("watch expects --day"))?;
+    Ok(WatchArgs { day, parts, input, interval, release })
+}
+
+fn parse_sweep(args: &[String]) -> Result<SweepArgs, String> {
+    let mut input = InputKind::Stdin;
+    let mut window = 3;
+    let mut every: Option<usize> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = InputKind::from_arg(option_value(arg, &mut args)?),
+            "--window" | "-w" => {
+                window = parse_count(arg, option_value(arg, &mut args)?)?;
+                if window == 0 {
+                    return Err(String::from("--window must be at least 1"));
+                }
+            },
+            "--every" => {
+                let value = parse_count(arg, option_value(arg, &mut args)?)?;
+                every = Some(value).filter(|every| *every > 0);
+            },
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(SweepArgs { input, window, every })
+}
+
+fn parse_verify(args: &[String]) -> Result<VerifyArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut datasets = Dataset::BOTH.to_vec();
+    let mut record = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--all" | "-a" => day = None,
+            "--part" | "-p" => {
+                parts = vec![parse_part(option_value(arg, &mut args)?)?];
+            },
+            "--input" | "-i" => {
+                let value = option_value(arg, &mut args)?;
+                let dataset = Dataset::from_name(value)
+                    .ok_or(format!("verify only knows --input test or --input real, not {}", value))?;
+                datasets = vec![dataset];
+            },
+            "--record" => record = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(VerifyArgs { day, parts, datasets, record })
+}
+
+/// Parses the value of `--warmup` and `--samples`
+fn parse_count(option: &str, value: &str) -> Result<usize, String> {
+    value.parse::<usize>().map_err(|_| format!("{} expects a number, not {}", option, value))
+}
+
+fn parse_bench(args: &[String]) -> Result<BenchArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut input = InputKind::Real;
+    let mut config = BenchConfig::default();
+    let mut compare = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--all" | "-a" => day = None,
+            "--part" | "-p" => {
+                parts = vec![parse_part(option_value(arg, &mut args)?)?];
+            },
+            "--input" | "-i" => input = InputKind::from_arg(option_value(arg, &mut args)?),
+            "--warmup" => config.warmup = parse_count(arg, option_value(arg, &mut args)?)?,
+            "--samples" => {
+                config.samples = parse_count(arg, option_value(arg, &mut args)?)?;
+                if config.samples == 0 {
+                    return Err(String::from("--samples must be at least 1"));
+                }
+            },
+            "--compare" => compare = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    if input == InputKind::Stdin {
+        return Err(String::from("bench reads its input several times, use a file instead of stdin"));
+    }
+
+    if day.is_none() && matches!(input, InputKind::Path(_)) {
+        return Err(String::from("--input PATH needs a single --day"));
+    }
+
+    Ok(BenchArgs { day, parts, input, config, compare })
+}
+
+fn parse_new_day(args: &[String]) -> Result<NewDayArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut title: Option<String> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" | "-t" => title = Some(option_value(arg, &mut args)?.clone()),
+            value if day.is_none() => day = Some(parse_day(value)?),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    let day = day.ok_or(String::from("new-day expects the number of the day"))?;
+    Ok(NewDayArgs { day, title: title.unwrap_or(format!("Day {}", day)) })
+}
+
+fn parse_examples(args: &[String]) -> Result<ExamplesArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut write = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--all" | "-a" => day = None,
+            "--write" => write = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(ExamplesArgs { day, write })
+}
+
+fn parse_fetch(args: &[String]) -> Result<FetchArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut resources = Resource::BOTH.to_vec();
+    let mut config = PathBuf::from(config::CONFIG_FILE);
+    let mut base_url: Option<String> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--only" => {
+                let value = option_value(arg, &mut args)?;
+                let resource = Resource::from_name(value)
+                    .ok_or(format!("Can only fetch task or input, not {}", value))?;
+                resources = vec![resource];
+            },
+            "--config" => config = PathBuf::from(option_value(arg, &mut args)?),
+            "--base-url" => base_url = Some(option_value(arg, &mut args)?.trim_end_matches('/').to_string()),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    let day = day.ok_or(String::from("fetch expects --day"))?;
+    Ok(FetchArgs { day, resources, config, base_url })
+}
+
+fn parse_submit(args: &[String]) -> Result<SubmitArgs, String> {
+    let mut day: Option<u8> = None;
+    let mut part: Option<Part> = None;
+    let mut answer: Option<String> = None;
+    let mut config = PathBuf::from(config::CONFIG_FILE);
+    let mut base_url: Option<String> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                day = Some(parse_day(option_value(arg, &mut args)?)?);
+            },
+            "--part" | "-p" => {
+                part = Some(parse_part(option_value(arg, &mut args)?)?);
+            },
+            "--answer" => answer = Some(option_value(arg, &mut args)?.clone()),
+            "--config" => config = PathBuf::from(option_value(arg, &mut args)?),
+            "--base-url" => base_url = Some(option_value(arg, &mut args)?.trim_end_matches('/').to_string()),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    let day = day.ok_or(String::from("submit expects --day"))?;
+    let part = part.ok_or(String::from("submit expects --part"))?;
+    Ok(SubmitArgs { day, part, answer, config, base_url })
+}
+
+fn parse_mock_server(args: &[String]) -> Result<MockServerArgs, String> {
+    let mut port = 8080;
+    let mut dir = PathBuf::from("mock");
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                let value = option_value(arg, &mut args)?;
+                port = value.parse::<u16>().map_err(|_| format!("Invalid port {}", value))?;
+            },
+            "--dir" => dir = PathBuf::from(option_value(arg, &mut args)?),
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(MockServerArgs { port, dir })
+}
+
+/// Options of the sub commands which are followed by a value. That value is never taken for a
+/// verbosity flag, so `--input -v` still reads the file `-v`
+const VALUE_OPTIONS: [&str; 25] = [
+    "--day", "-d", "--part", "-p", "--input", "-i", "--format", "-f", "--jobs", "-j", "--timeout",
+    "--interval", "--window", "-w", "--every", "--warmup", "--samples", "--title", "-t", "--only",
+    "--answer", "--config", "--base-url", "--port", "--dir",
+];
+
+/// Takes the verbosity flags (`-q`, `-v`, `-vv`, ...) out of `args`. They may appear before the
+/// sub command or between its options, but not where an option expects its value.
+/// Every `v` counts, so `-v -v` is the same as `-vv`; `-q` wins over all of them.
+/// Returns the requested log level and the remaining arguments
+pub fn extract_verbosity(args: &[String]) -> (Level, Vec<String>) {
+    let mut verbosity = 0;
+    let mut quiet = false;
+    let mut rest = Vec::new();
+    let mut is_value = false;
+
+    for arg in args {
+        if is_value {
+            is_value = false;
+            rest.push(arg.clone());
+            continue;
+        }
+
+        match arg.as_str() {
+            "-q" | "--quiet" => quiet = true,
+            "--verbose" => verbosity += 1,
+            flag if flag.len() > 1 && flag.starts_with('-') && flag[1..].chars().all(|c| c == 'v') => {
+                verbosity += flag.len() - 1;
+            },
+            _ => {
+                is_value = VALUE_OPTIONS.contains(&arg.as_str());
+                rest.push(arg.clone());
+            },
+        }
+    }
+
+    let level = match (quiet, verbosity) {
+        (true, _) => Level::Error,
+        (false, 0) => Level::Warn,
+        (false, 1) => Level::Info,
+        (false, _) => Level::Debug,
+    };
+    (level, rest)
+}
+
+/// Parses the command line arguments (without the program name)
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|a| a.as_str()) {
+        None | Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some("run") => Ok(Command::Run(parse_run(&args[1..])?)),
+        Some("watch") => Ok(Command::Watch(parse_watch(&args[1..])?)),
+        Some("sweep") => Ok(Command::Sweep(parse_sweep(&args[1..])?)),
+        Some("verify") => Ok(Command::Verify(parse_verify(&args[1..])?)),
+        Some("bench") => Ok(Command::Bench(parse_bench(&args[1..])?)),
+        Some("new-day") => Ok(Command::NewDay(parse_new_day(&args[1..])?)),
+        Some("examples") => Ok(Command::Examples(parse_examples(&args[1..])?)),
+        Some("fetch") => Ok(Command::Fetch(parse_fetch(&args[1..])?)),
+        Some("submit") => Ok(Command::Submit(parse_submit(&args[1..])?)),
+        Some("mock-server") => Ok(Command::MockServer(parse_mock_server(&args[1..])?)),
+        Some(other) => Err(format!("Unknown command {}", other)),
+    }
+}
+
+/// All registered days or just `day`
+fn select_days(day: Option<u8>) -> Result<Vec<&'static dyn AnySolution>, String> {
+    match day {
+        None => Ok(registry::all().to_vec()),
+        Some(day) => registry::find(day)
+            .map(|found| vec![found])
+            .ok_or(format!("Day {} is not registered", day)),
+    }
+}
+
+/// Executes a `run` command and returns the process exit code
+fn execute_run(args: &RunArgs) -> i32 {
+    let days = match select_days(args.day) {
+        Ok(days) => days,
+        Err(msg) => {
+            error!("{}", msg);
+            return 2;
+        }
+    };
+
+    if args.memory && !memory::enable() {
+        warning!("The counting allocator is not installed, memory can't be profiled");
+    }
+
+    let results = runner::run_days_parallel(&days, &args.parts, &args.input, args.trim, &args.pool);
+    output::print(&results, args.format);
+
+    if results.iter().all(|r| r.is_ok()) { 0 } else { 1 }
+}
+
+/// Executes a `watch` command in the current directory (the crate root); runs until killed
+fn execute_watch(args: &WatchArgs) -> i32 {
+    if registry::find(args.day).is_none() {
+        error!("Day {} is not registered", args.day);
+        return 2;
+    }
+
+    match watch::watch(args) {
+        Ok(()) => 0,
+        Err(msg) => {
+            error!("{}", msg);
+            1
+        }
+    }
+}
+
+/// Executes a `sweep` command: streams day 1 readings and prints the number of increases
+fn execute_sweep(args: &SweepArgs) -> i32 {
+    let reader: Box<dyn BufRead> = match args.input.path_for(1) {
+        None => Box::new(io::stdin().lock()),
+        Some(path) => match File::open(&path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(err) => {
+                error!("Couldn't open {}: {}", path.display(), err);
+                return 1;
+            }
+        },
+    };
+
+    let mut sweep = Sweep::new(reader, args.window);
+    let outcome: Result<(), SweepError> = sweep.try_for_each(|progress| {
+        let progress = progress?;
+        if args.every.is_some_and(|every| progress.readings % every == 0) {
+            println!("{} readings: {} increases", progress.readings, progress.increases);
+        }
+        Ok(())
+    });
+
+    match outcome {
+        Ok(()) => {
+            println!("{}", sweep.progress().increases);
+            0
+        },
+        Err(err) => {
+            error!("{}", err);
+            1
+        }
+    }
+}
+
+/// Executes a `verify` command; fails if any answer is wrong or couldn't be computed
+fn execute_verify(args: &VerifyArgs) -> i32 {
+    let days = match select_days(args.day) {
+        Ok(days) => days,
+        Err(msg) => {
+            error!("{}", msg);
+            return 2;
+        }
+    };
+
+    let verifications = verify::verify(&days, &args.parts, &args.datasets, args.record);
+    verify::print_report(&verifications);
+
+    if verifications.iter().any(|v| v.verdict.is_error()) { 1 } else { 0 }
+}
+
+/// Executes a `bench` command; fails if any step couldn't be benchmarked
+fn execute_bench(args: &BenchArgs) -> i32 {
+    let days = match select_days(args.day) {
+        Ok(days) => days,
+        Err(msg) => {
+            error!("{}", msg);
+            return 2;
+        }
+    };
+
+    let mut benches = Vec::new();
+    let mut variants = Vec::new();
+    let mut failed = false;
+    for day in days {
+        let data = match args.input.load(day.day(), true) {
+            Ok(data) => data,
+            Err(err) => {
+                error!("Day {}: {}", day.day(), err);
+                failed = true;
+                continue;
+            }
+        };
+
+        benches.push(bench::bench_day(day, &args.parts, &data, &args.config));
+
+        if args.compare {
+            match bench::compare_day(day.day(), &args.parts, data.lines(), &args.config) {
+                Some(Ok(found)) => variants.extend(found),
+                Some(Err(err)) => {
+                    error!("Day {}: {}", day.day(), err);
+                    failed = true;
+                },
+                None => {},
+            }
+        }
+    }
+
+    bench::print_report(&benches);
+    if args.compare {
+        println!();
+        bench::print_comparison(&variants);
+    }
+
+    failed |= !benches.iter().all(|b| b.is_ok());
+    if failed { 1 } else { 0 }
+}
+
+/// Executes a `new-day` command in the current directory (the crate root)
+fn execute_new_day(args: &NewDayArgs) -> i32 {
+    match scaffold::new_day(Path::new("."), args.day, &args.title) {
+        Ok(files) => {
+            for file in files {
+                println!("{}", file.display());
+            }
+            info!("Paste the riddle into task.txt, the example into testinput.txt and rebuild");
+            0
+        },
+        Err(msg) => {
+            error!("{}", msg);
+            1
+        }
+    }
+}
+
+/// Executes an `examples` command in the current directory (the crate root); fails if any day drifted
+fn execute_examples(args: &ExamplesArgs) -> i32 {
+    let days = match select_days(args.day) {
+        Ok(days) => days,
+        Err(msg) => {
+            error!("{}", msg);
+            return 2;
+        }
+    };
+
+    let days: Vec<u8> = days.iter().map(|day| day.day()).collect();
+    let (reports, failed) = examples::check_days(&days, args.write);
+    examples::print_report(&reports);
+
+    if failed || reports.iter().any(|report| report.has_drift()) { 1 } else { 0 }
+}
+
+/// Loads the config at `path`, `base_url` overrides the configured one
+fn load_config(path: &Path, base_url: &Option<String>) -> Result<Config, String> {
+    let mut config = Config::load(path)?;
+    if let Some(base_url) = base_url {
+        config.base_url = base_url.clone();
+    }
+    Ok(config)
+}
+
+/// Executes a `fetch` command in the current directory (the crate root); fails if any file couldn't be fetched
+fn execute_fetch(args: &FetchArgs) -> i32 {
+    let config = match load_config(&args.config, &args.base_url) {
+        Ok(config) => config,
+        Err(msg) => {
+            error!("{}", msg);
+            return 2;
+        }
+    };
+
+    let mut fetcher = Fetcher::new(config, Path::new("."));
+    let mut failed = false;
+    for (resource, result) in args.resources.iter().zip(fetcher.fetch_day(args.day, &args.resources)) {
+        match result {
+            Ok(Fetched::Cached(path)) => println!("{} (cached)", path.display()),
+            Ok(Fetched::Downloaded(path)) => println!("{}", path.display()),
+            Err(err) => {
+                error!("Day {} {}: {}", args.day, resource, err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed { 1 } else { 0 }
+}
+
+/// What the solution of `day` computes for `part` of the real input, as it would be typed into the website
+fn compute_answer(day: u8, part: Part) -> Result<String, String> {
+    let solution = registry::find(day).ok_or(format!("Day {} is not registered", day))?;
+    let data = InputKind::Real.load(day, true).map_err(|err| err.to_string())?;
+
+    match runner::run_part(solution, part, &InputKind::Real, &data).outcome? {
+        Answer::Art(_) => Err(String::from("The answer is a drawing, read it and pass it with --answer")),
+        answer => Ok(answer.to_string()),
+    }
+}
+
+/// Executes a `submit` command in the current directory (the crate root); succeeds only for a correct answer
+fn execute_submit(args: &SubmitArgs) -> i32 {
+    let submitted = load_config(&args.config, &args.base_url).and_then(|config| {
+        let answer = match &args.answer {
+            Some(answer) => answer.clone(),
+            None => compute_answer(args.day, args.part)?,
+        };
+        submit::submit(&config, Path::new("."), args.day, args.part, &answer)
+    });
+
+    match submitted {
+        Ok(submission) => {
+            println!("{}", submission.message);
+            if submission.outcome == Outcome::Correct { 0 } else { 1 }
+        },
+        Err(msg) => {
+            error!("{}", msg);
+            1
+        }
+    }
+}
+
+/// Executes a `mock-server` command, serves until the process is killed
+fn execute_mock_server(args: &MockServerArgs) -> i32 {
+    let listener = match TcpListener::bind(("127.0.0.1", args.port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("Couldn't listen on port {}: {}", args.port, err);
+            return 1;
+        }
+    };
+
+    info!("Serving {} on http://127.0.0.1:{} (use it with fetch --base-url)", args.dir.display(), args.port);
+    mock_server::serve(listener, &mock_server::serve_dir(args.dir.clone()), None);
+    0
+}
+
+/// Entry point: parses `args` and dispatches to the requested command.
+/// Returns the exit code (0 = success, 1 = some solution failed, 2 = usage error)
+pub fn run(args: &[String]) -> i32 {
+    let (level, args) = extract_verbosity(args);
+    log::set_level(level);
+
+    match parse_args(&args) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            0
+        },
+        Ok(Command::Run(run_args)) => execute_run(&run_args),
+        Ok(Command::Watch(watch_args)) => execute_watch(&watch_args),
+        Ok(Command::Sweep(sweep_args)) => execute_sweep(&sweep_args),
+        Ok(Command::Verify(verify_args)) => execute_verify(&verify_args),
+        Ok(Command::Bench(bench_args)) => execute_bench(&bench_args),
+        Ok(Command::NewDay(new_day_args)) => execute_new_day(&new_day_args),
+        Ok(Command::Examples(examples_args)) => execute_examples(&examples_args),
+        Ok(Command::Fetch(fetch_args)) => execute_fetch(&fetch_args),
+        Ok(Command::Submit(submit_args)) => execute_submit(&submit_args),
+        Ok(Command::MockServer(mock_server_args)) => execute_mock_server(&mock_server_args),
+        Err(msg) => {
+            error!("{}", msg);
+            eprintln!("\n{}", USAGE);
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_a_single_day() {
+        assert_eq!(parse_args(&args("run --day 5 --part 2 --input test")), Ok(Command::Run(RunArgs {
+            day: Some(5),
+            parts: vec![Part::Two],
+            input: InputKind::Test,
+            trim: true,
+            format: OutputFormat::Table,
+            pool: PoolConfig::default(),
+            memory: false,
+        })));
+    }
+
+    #[test]
+    fn parses_all_days_with_defaults() {
+        assert_eq!(parse_args(&args("run --all --no-trim --format json")), Ok(Command::Run(RunArgs {
+            day: None,
+            parts: Part::BOTH.to_vec(),
+            input: InputKind::Real,
+            trim: false,
+            format: OutputFormat::Json,
+            pool: PoolConfig::default(),
+            memory: false,
+        })));
+    }
+
+    #[test]
+    fn parses_watch() {
+        assert_eq!(parse_args(&args("watch --day 11 --part 2 --input test --interval 200 --release")), Ok(Command::Watch(WatchArgs {
+            day: 11,
+            parts: vec![Part::Two],
+            input: InputKind::Test,
+            interval: Duration::from_millis(200),
+            release: true,
+        })));
+        assert!(parse_args(&args("watch")).is_err());
+        assert!(parse_args(&args("watch --day 11 --input -")).is_err());
+    }
+
+    #[test]
+    fn parses_sweep() {
+        assert_eq!(parse_args(&args("sweep")), Ok(Command::Sweep(SweepArgs { input: InputKind::Stdin, window: 3, every: None })));
+        assert_eq!(parse_args(&args("sweep --input test --window 1 --every 1000")), Ok(Command::Sweep(SweepArgs {
+            input: InputKind::Test,
+            window: 1,
+            every: Some(1000),
+        })));
+        assert!(parse_args(&args("sweep --window 0")).is_err());
+    }
+
+    #[test]
+    fn parses_jobs_and_timeout() {
+        let parsed = parse_args(&args("run --all --jobs 3 --timeout 1.5"));
+        assert!(matches!(parsed, Ok(Command::Run(RunArgs { pool: PoolConfig { workers: 3, timeout: Some(t) }, .. }))
+            if t == Duration::from_millis(1500)));
+        assert!(parse_args(&args("run --all --jobs 0")).is_err());
+        assert!(matches!(parse_args(&args("run --day 14 --memory")), Ok(Command::Run(RunArgs { memory: true, .. }))));
+        assert!(parse_args(&args("run --all --timeout -1")).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_combinations() {
+        assert!(parse_args(&args("run")).is_err());
+        assert!(parse_args(&args("run --day 1 --all")).is_err());
+        assert!(parse_args(&args("run --all --input -")).is_err());
+        assert!(parse_args(&args("run --day 1 --part 3")).is_err());
+        assert!(parse_args(&args("run --all --format xml")).is_err());
+        assert!(parse_args(&args("dance")).is_err());
+    }
+
+    #[test]
+    fn parses_bench_options() {
+        assert_eq!(parse_args(&args("bench --day 1 --warmup 0 --samples 3 --compare")), Ok(Command::Bench(BenchArgs {
+            day: Some(1),
+            parts: Part::BOTH.to_vec(),
+            input: InputKind::Real,
+            config: BenchConfig { warmup: 0, samples: 3 },
+            compare: true,
+        })));
+        assert!(parse_args(&args("bench --samples 0")).is_err());
+        assert!(parse_args(&args("bench --day 1 --input -")).is_err());
+    }
+
+    #[test]
+    fn parses_new_day() {
+        assert_eq!(parse_args(&args("new-day 15 --title Chiton")), Ok(Command::NewDay(NewDayArgs {
+            day: 15,
+            title: String::from("Chiton"),
+        })));
+        assert_eq!(parse_args(&args("new-day 16")), Ok(Command::NewDay(NewDayArgs {
+            day: 16,
+            title: String::from("Day 16"),
+        })));
+        assert!(parse_args(&args("new-day")).is_err());
+        assert!(parse_args(&args("new-day 15 16")).is_err());
+    }
+
+    #[test]
+    fn parses_examples() {
+        assert_eq!(parse_args(&args("examples --day 8 --write")), Ok(Command::Examples(ExamplesArgs {
+            day: Some(8),
+            write: true,
+        })));
+        assert_eq!(parse_args(&args("examples")), Ok(Command::Examples(ExamplesArgs { day: None, write: false })));
+    }
+
+    #[test]
+    fn parses_fetch_submit_and_mock_server() {
+        assert_eq!(parse_args(&args("fetch --day 3 --only input --base-url http://127.0.0.1:8080/")), Ok(Command::Fetch(FetchArgs {
+            day: 3,
+            resources: vec![Resource::Input],
+            config: PathBuf::from("aoc.conf"),
+            base_url: Some(String::from("http://127.0.0.1:8080")),
+        })));
+        assert!(parse_args(&args("fetch")).is_err());
+        assert!(parse_args(&args("fetch --day 3 --only answers")).is_err());
+
+        assert_eq!(parse_args(&args("submit --day 2 --part 1 --answer 150")), Ok(Command::Submit(SubmitArgs {
+            day: 2,
+            part: Part::One,
+            answer: Some(String::from("150")),
+            config: PathBuf::from("aoc.conf"),
+            base_url: None,
+        })));
+        assert!(parse_args(&args("submit --day 2")).is_err());
+
+        assert_eq!(parse_args(&args("mock-server --port 9000")), Ok(Command::MockServer(MockServerArgs {
+            port: 9000,
+            dir: PathBuf::from("mock"),
+        })));
+    }
+
+    #[test]
+    fn extracts_verbosity_flags() {
+        assert_eq!(extract_verbosity(&args("run -v --day 1")), (Level::Info, args("run --day 1")));
+        assert_eq!(extract_verbosity(&args("-vv run --day 1")), (Level::Debug, args("run --day 1")));
+        assert_eq!(extract_verbosity(&args("run -v --all")), (Level::Info, args("run --all")));
+        // repeated flags add up like the letters of `-vv`
+        assert_eq!(extract_verbosity(&args("-v -v run --day 1")), (Level::Debug, args("run --day 1")));
+        assert_eq!(extract_verbosity(&args("run -v --all --verbose")), (Level::Debug, args("run --all")));
+        assert_eq!(extract_verbosity(&args("-vv run -q")), (Level::Error, args("run")));
+        assert_eq!(extract_verbosity(&args("run --all -q")), (Level::Error, args("run --all")));
+        assert_eq!(extract_verbosity(&args("run --all")), (Level::Warn, args("run --all")));
+
+        // option values stay what they are
+        assert_eq!(extract_verbosity(&args("-v run --day 1 --input -vv")), (Level::Info, args("run --day 1 --input -vv")));
+        assert_eq!(extract_verbosity(&args("new-day 15 --title -v -q")), (Level::Error, args("new-day 15 --title -v")));
+        assert_eq!(parse_args(&extract_verbosity(&args("submit --day 2 --part 1 --answer -q")).1).unwrap(), Command::Submit(SubmitArgs {
+            day: 2,
+            part: Part::One,
+            answer: Some(String::from("-q")),
+            config: PathBuf::from(config::CONFIG_FILE),
+            base_url: None,
+        }));
+    }
+
+    #[test]
+    fn knows_every_option_with_a_value() {
+        // `--name <VALUE>` in the usage
+        for option in USAGE.split(['[', ']', '|', '\n']).filter_map(|part| part.trim().strip_suffix('>')) {
+            if let Some((name, _)) = option.split_once(" <").filter(|(name, _)| name.starts_with("--")) {
+                assert!(VALUE_OPTIONS.contains(&name), "{} is missing in VALUE_OPTIONS", name);
+            }
+        }
+    }
+
+    #[test]
+    fn no_arguments_shows_help() {
+        assert_eq!(parse_args(&[]), Ok(Command::Help));
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::parse_error::ParseError;
+
+/// The default config file (relative to the crate root). It holds the session token, so it is not committed
+pub const CONFIG_FILE: &str = "aoc.conf";
+
+/// Settings for talking to adventofcode.com, read from a file like
+/// ```text
+/// # the value of the `session` cookie after logging in
+/// session = 53616c7465645f5f...
+/// base_url = https://adventofcode.com
+/// year = 2021
+/// min_interval_ms = 5000
+/// ```
+/// Every key is optional
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// the `session` cookie identifying you (needed for personal inputs)
+    pub session: Option<String>,
+    /// where the puzzles are; point it at a mock server for offline testing
+    pub base_url: String,
+    pub year: u16,
+    /// minimum time between two requests
+    pub min_interval: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: String::from("https://adventofcode.com"),
+            year: 2021,
+            min_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Config {
+    /// Reads `key = value` lines; blank lines and lines starting with `#` are ignored
+    pub fn parse(lines: &[String]) -> Result<Self, ParseError> {
+        let mut config = Self::default();
+
+        for (i, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or(ParseError::at_line(i, line, "<key> = <value>"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "session" => config.session = Some(String::from(value)).filter(|s| !s.is_empty()),
+                "base_url" => config.base_url = String::from(value.trim_end_matches('/')),
+                "year" => config.year = value.parse().map_err(|_| ParseError::at_part(i, line, value, "<year>"))?,
+                "min_interval_ms" => config.min_interval = value
+                    .parse()
+                    .map(Duration::from_millis)
+                    .map_err(|_| ParseError::at_part(i, line, value, "<milliseconds>"))?,
+                _ => return Err(ParseError::at_part(i, line, key, "session|base_url|year|min_interval_ms")),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads the config at `path`; a missing file gives the defaults
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(format!("Couldn't read {}: {}", path.display(), err)),
+        };
+
+        let lines: Vec<String> = content.lines().map(String::from).collect();
+        Self::parse(&lines).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// The page describing `day`
+    pub fn task_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}", self.base_url, self.year, day)
+    }
+
+    /// The personal input of `day`
+    pub fn input_url(&self, day: u8) -> String {
+        format!("{}/input", self.task_url(day))
+    }
+
+    /// Where answers of `day` are posted to
+    pub fn answer_url(&self, day: u8) -> String {
+        format!("{}/answer", self.task_url(day))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &[&str]) -> Vec<String> {
+        input.iter().map(|line| String::from(*line)).collect()
+    }
+
+    #[test]
+    fn reads_all_keys() {
+        let config = Config::parse(&lines(&[
+            "# comment",
+            "session = abc123",
+            "",
+            "base_url = http://127.0.0.1:8080/",
+            "year=2022",
+            "min_interval_ms = 250",
+        ])).unwrap();
+
+        assert_eq!(config, Config {
+            session: Some(String::from("abc123")),
+            base_url: String::from("http://127.0.0.1:8080"),
+            year: 2022,
+            min_interval: Duration::from_millis(250),
+        });
+        assert_eq!(config.input_url(3), "http://127.0.0.1:8080/2022/day/3/input");
+    }
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let config = Config::parse(&lines(&["session ="])).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.task_url(1), "https://adventofcode.com/2021/day/1");
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let err = Config::parse(&lines(&["year = soon"])).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (1, 8, "<year>"));
+        assert_eq!(Config::parse(&lines(&["", "colour = red"])).unwrap_err().line, 2);
+        assert!(Config::parse(&lines(&["session abc"])).is_err());
+    }
+}
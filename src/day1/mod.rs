@@ -1,3 +1,16 @@
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
+use crate::bench::Variant;
+use crate::parse_error::ParseError;
+use crate::parser::{integer, Section};
+use crate::solution::{Answer, Part, Solution, SolveError};
+
+mod series;
+mod stream;
+pub use series::{Aggregate, Compare, Level, Run, Sample, Tracker, Window};
+pub use stream::{count_increases, Progress, Sweep, SweepError};
+
 /// Straight-forward implementation which just compares two numbers
 pub fn task1(data: &Vec<u64>) -> u64 {
 
@@ -32,7 +45,12 @@ pub fn task1(data: &Vec<u64>) -> u64 {
 ///   `[[6,9], ..., [12, 15]]`
 /// - folds those windows to a single increasing number if the second entry is bigger than the first
 ///   `9 > 6 ? + 1 + ... + 15 > 12 ? + 1`
-pub fn task2_functional(data: &Vec<u64>, window_size: usize) -> u64 {
+pub fn task2_functional(data: &[u64], window_size: usize) -> u64 {
+    // `windows(0)` panics, and there is nothing to compare anyway
+    if window_size == 0 {
+        return 0;
+    }
+
     let collapsed= data
         .windows(window_size)
         .map(|w| w.iter().sum())
@@ -74,6 +92,11 @@ pub fn task2(data: Vec<u64>, window_size: usize) -> u64 {
     // Rust may optimize the vector versions, too, though
     let data_as_array = data.as_slice();
     let mut up_count: u64 = 0;
+    // less than two windows, nothing to compare (and the first sum below would be out of bounds)
+    if window_size == 0 || data.len() <= window_size {
+        return 0;
+    }
+
     let mut a: u64 = data_as_array[0..window_size].iter().sum();
 
     for i in window_size..data.len() {
@@ -87,3 +110,57 @@ pub fn task2(data: Vec<u64>, window_size: usize) -> u64 {
 
     return up_count; // 1627
 }
+
+/// The different implementations of part 2 (window size 3), so they can be benchmarked against each other.
+/// `task2` and `task2_slow` take ownership of the data, so their timings include a copy of the input
+pub const VARIANTS: [Variant<Vec<u64>>; 4] = [
+    Variant { name: "task2_functional", part: Part::Two, run: |data| task2_functional(data, 3).into() },
+    Variant { name: "task2_slow", part: Part::Two, run: |data| task2_slow(data.clone(), 3).into() },
+    Variant { name: "task2", part: Part::Two, run: |data| task2(data.clone(), 3).into() },
+    Variant { name: "series", part: Part::Two, run: |data| Window::new(3, Aggregate::Sum).count(data, Compare::Increase).into() },
+];
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u64>;
+
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Sonar Sweep"
+    }
+
+    /// Every line is a single depth measurement
+    fn parse(&self, lines: &[String]) -> Result<Vec<u64>, ParseError> {
+        Section::whole(lines).each_line(integer("<depth>"))
+    }
+
+    fn part1(&self, input: &Vec<u64>) -> Result<Answer, SolveError> {
+        Ok(task1(input).into())
+    }
+
+    /// uses the sliding window version with the window size of 3 from the task
+    fn part2(&self, input: &Vec<u64>) -> Result<Answer, SolveError> {
+        Ok(task2_functional(input, 3).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_inputs_have_no_increases() {
+        for data in [vec![], vec![1], vec![1, 2, 3]] {
+            for window_size in [0, 1, 3, 5] {
+                let expected = Window::new(window_size, Aggregate::Sum).count(&data, Compare::Increase);
+                assert_eq!(task2_functional(&data, window_size), expected);
+                assert_eq!(task2_slow(data.clone(), window_size), expected);
+                assert_eq!(task2(data.clone(), window_size), expected);
+            }
+        }
+    }
+}
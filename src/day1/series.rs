@@ -0,0 +1,293 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A single reading. Widened to `i128`, so neither signed readings nor long sums of `u64`s overflow
+pub trait Sample: Copy {
+    fn widen(self) -> i128;
+}
+
+impl Sample for u64 {
+    fn widen(self) -> i128 {
+        self as i128
+    }
+}
+
+impl Sample for i64 {
+    fn widen(self) -> i128 {
+        self as i128
+    }
+}
+
+/// How the readings of a window are collapsed into one [Level]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+/// The aggregated value of a window. Means are kept as a fraction so comparing them is exact
+#[derive(Debug, Clone, Copy)]
+pub struct Level {
+    total: i128,
+    /// 1 unless it is a mean
+    count: u64,
+}
+
+impl Level {
+    pub fn whole(value: i128) -> Self {
+        Self { total: value, count: 1 }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.total as f64 / self.count as f64
+    }
+
+    /// `self - other` scaled by both denominators (only the sign and relative size matter)
+    fn difference(&self, other: &Level) -> i128 {
+        self.total * other.count as i128 - other.total * self.count as i128
+    }
+}
+
+impl PartialEq for Level {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Level {}
+
+impl Ord for Level {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.difference(other).cmp(&0)
+    }
+}
+
+impl PartialOrd for Level {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.count {
+            1 => write!(f, "{}", self.total),
+            _ => write!(f, "{}", self.as_f64()),
+        }
+    }
+}
+
+/// What has to hold between a window and the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compare {
+    Increase,
+    Decrease,
+    Unchanged,
+    /// the level changed by at least this much (negative: dropped by at most that much)
+    Threshold(i64),
+}
+
+impl Compare {
+    pub fn holds(&self, previous: &Level, current: &Level) -> bool {
+        match self {
+            Compare::Increase => current > previous,
+            Compare::Decrease => current < previous,
+            Compare::Unchanged => current == previous,
+            Compare::Threshold(by) => {
+                let scale = (previous.count as i128) * (current.count as i128);
+                current.difference(previous) >= *by as i128 * scale
+            },
+        }
+    }
+}
+
+/// Consecutive windows which all fulfilled the comparison with the window before
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    /// index of the first window (which is also the index of its first reading)
+    pub start: usize,
+    /// number of windows, so a single match is a run of 2
+    pub length: usize,
+}
+
+/// Sliding windows of `size` readings, collapsed with `aggregate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub size: usize,
+    pub aggregate: Aggregate,
+}
+
+impl Window {
+    pub fn new(size: usize, aggregate: Aggregate) -> Self {
+        Self { size, aggregate }
+    }
+
+    /// The level of every window. Empty if there are less readings than `size` (or `size` is 0)
+    pub fn levels<T: Sample>(&self, data: &[T]) -> Vec<Level> {
+        let mut tracker = Tracker::new(*self, Compare::Increase);
+        data.iter().filter_map(|sample| tracker.push(*sample)).collect()
+    }
+
+    /// Feeds all `samples` through a [Tracker], the iterator is consumed on the go
+    pub fn track<T: Sample>(&self, samples: impl IntoIterator<Item = T>, compare: Compare) -> Tracker {
+        let mut tracker = Tracker::new(*self, compare);
+        for sample in samples {
+            tracker.push(sample);
+        }
+        tracker
+    }
+
+    /// How often a window fulfils `compare` with the window before it
+    pub fn count<T: Sample>(&self, data: &[T], compare: Compare) -> u64 {
+        self.track(data.iter().copied(), compare).matches()
+    }
+
+    /// The longest stretch of windows fulfilling `compare` (the first one if there are several)
+    pub fn longest_run<T: Sample>(&self, data: &[T], compare: Compare) -> Option<Run> {
+        self.track(data.iter().copied(), compare).longest_run()
+    }
+}
+
+/// Looks at one reading at a time and only keeps the current window in memory
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    window: Window,
+    compare: Compare,
+    buffer: VecDeque<i128>,
+    sum: i128,
+    pushed: usize,
+    previous: Option<Level>,
+    matches: u64,
+    current: Option<Run>,
+    longest: Option<Run>,
+}
+
+impl Tracker {
+    pub fn new(window: Window, compare: Compare) -> Self {
+        Self {
+            window,
+            compare,
+            buffer: VecDeque::with_capacity(window.size),
+            sum: 0,
+            pushed: 0,
+            previous: None,
+            matches: 0,
+            current: None,
+            longest: None,
+        }
+    }
+
+    /// Adds a reading. Returns the level of the window it completes (`None` while the first window fills up)
+    pub fn push<T: Sample>(&mut self, sample: T) -> Option<Level> {
+        let size = self.window.size;
+        if size == 0 {
+            return None;
+        }
+
+        let value = sample.widen();
+        self.pushed += 1;
+        self.buffer.push_back(value);
+        self.sum += value;
+        if self.buffer.len() > size {
+            self.sum -= self.buffer.pop_front().unwrap();
+        }
+        if self.buffer.len() < size {
+            return None;
+        }
+
+        let level = match self.window.aggregate {
+            Aggregate::Sum => Level::whole(self.sum),
+            Aggregate::Mean => Level { total: self.sum, count: size as u64 },
+            Aggregate::Min => Level::whole(*self.buffer.iter().min().unwrap()),
+            Aggregate::Max => Level::whole(*self.buffer.iter().max().unwrap()),
+        };
+
+        let index = self.pushed - size;
+        if let Some(previous) = self.previous {
+            if self.compare.holds(&previous, &level) {
+                self.matches += 1;
+                let run = match self.current {
+                    Some(run) => Run { start: run.start, length: run.length + 1 },
+                    None => Run { start: index - 1, length: 2 },
+                };
+                if self.longest.is_none_or(|longest| run.length > longest.length) {
+                    self.longest = Some(run);
+                }
+                self.current = Some(run);
+            } else {
+                self.current = None;
+            }
+        }
+        self.previous = Some(level);
+        Some(level)
+    }
+
+    /// How many windows fulfilled the comparison so far
+    pub fn matches(&self) -> u64 {
+        self.matches
+    }
+
+    pub fn longest_run(&self) -> Option<Run> {
+        self.longest
+    }
+
+    /// Number of readings seen so far
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u64; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn counts_like_the_task() {
+        assert_eq!(Window::new(1, Aggregate::Sum).count(&EXAMPLE, Compare::Increase), 7);
+        assert_eq!(Window::new(3, Aggregate::Sum).count(&EXAMPLE, Compare::Increase), 5);
+        // a mean changes in the same direction as the sum
+        assert_eq!(Window::new(3, Aggregate::Mean).count(&EXAMPLE, Compare::Increase), 5);
+        assert_eq!(Window::new(3, Aggregate::Sum).count(&EXAMPLE, Compare::Unchanged), 1);
+        assert_eq!(Window::new(1, Aggregate::Sum).count(&EXAMPLE, Compare::Threshold(10)), 2);
+        assert_eq!(Window::new(1, Aggregate::Sum).count(&EXAMPLE, Compare::Threshold(-10)), 9);
+    }
+
+    #[test]
+    fn aggregates_windows() {
+        let data: [i64; 5] = [3, -1, 4, -1, 5];
+        let levels = |aggregate| Window::new(2, aggregate).levels(&data).iter().map(|l| l.as_f64()).collect::<Vec<f64>>();
+        assert_eq!(levels(Aggregate::Sum), vec![2.0, 3.0, 3.0, 4.0]);
+        assert_eq!(levels(Aggregate::Mean), vec![1.0, 1.5, 1.5, 2.0]);
+        assert_eq!(levels(Aggregate::Min), vec![-1.0, -1.0, -1.0, -1.0]);
+        assert_eq!(levels(Aggregate::Max), vec![3.0, 4.0, 4.0, 5.0]);
+        assert_eq!(Window::new(2, Aggregate::Mean).levels(&data)[1].to_string(), "1.5");
+    }
+
+    #[test]
+    fn short_inputs_have_no_windows() {
+        for size in [0, 4, 100] {
+            let window = Window::new(size, Aggregate::Sum);
+            assert!(window.levels(&[1u64, 2, 3]).is_empty());
+            assert_eq!(window.count(&[1u64, 2, 3], Compare::Increase), 0);
+            assert_eq!(window.longest_run(&[] as &[u64], Compare::Increase), None);
+        }
+    }
+
+    #[test]
+    fn finds_the_longest_stretch() {
+        let window = Window::new(1, Aggregate::Sum);
+        // 199 200 208 210 | 200 207 240 269 | 260 263, both stretches are 4 long, the first one wins
+        assert_eq!(window.longest_run(&EXAMPLE, Compare::Increase), Some(Run { start: 0, length: 4 }));
+        assert_eq!(window.longest_run(&EXAMPLE, Compare::Decrease), Some(Run { start: 3, length: 2 }));
+
+        // streaming from an iterator gives the same as a slice
+        let tracker = window.track(EXAMPLE.iter().copied().chain(300..310), Compare::Increase);
+        assert_eq!(tracker.longest_run(), Some(Run { start: 8, length: 12 }));
+        assert_eq!(tracker.pushed(), 20);
+    }
+}
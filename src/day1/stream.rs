@@ -0,0 +1,186 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::parse_error::ParseError;
+use crate::parser::{integer, line};
+
+use super::series::{Aggregate, Compare, Tracker, Window};
+
+/// Why a sweep stopped early
+#[derive(Debug)]
+pub enum SweepError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepError::Io(err) => write!(f, "Couldn't read the readings: {}", err),
+            SweepError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+/// How far a sweep got after a reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// readings so far (blank lines don't count)
+    pub readings: usize,
+    /// increases of the window sum so far
+    pub increases: u64,
+}
+
+/// Reads one depth per line from `reader` and counts how often the sum of `window_size` readings increases,
+/// like [super::task2_functional] but without collecting the readings: only the current window is kept
+/// (in the ring buffer of a [Tracker]) and one line buffer is reused for all lines.
+/// Yields the progress after every reading, blank lines are skipped
+pub struct Sweep<R: BufRead> {
+    reader: R,
+    tracker: Tracker,
+    text: String,
+    /// index of the next line (for error messages)
+    index: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> Sweep<R> {
+    pub fn new(reader: R, window_size: usize) -> Self {
+        Self {
+            reader,
+            tracker: Tracker::new(Window::new(window_size, Aggregate::Sum), Compare::Increase),
+            text: String::new(),
+            index: 0,
+            failed: false,
+        }
+    }
+
+    /// Where the sweep currently stands
+    pub fn progress(&self) -> Progress {
+        Progress { readings: self.tracker.pushed(), increases: self.tracker.matches() }
+    }
+
+    /// Reads everything that's left and returns the final count
+    pub fn finish(mut self) -> Result<u64, SweepError> {
+        for progress in &mut self {
+            progress?;
+        }
+        Ok(self.tracker.matches())
+    }
+}
+
+impl<R: BufRead> Iterator for Sweep<R> {
+    type Item = Result<Progress, SweepError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // an error can't be recovered from, so we stop after the first one
+        if self.failed {
+            return None;
+        }
+
+        loop {
+            self.text.clear();
+            match self.reader.read_line(&mut self.text) {
+                Ok(0) => return None,
+                Ok(_) => {},
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(SweepError::Io(err)));
+                }
+            }
+
+            let index = self.index;
+            self.index += 1;
+            let text = self.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            return match line(index, text, integer::<u64>("<depth>")) {
+                Ok(depth) => {
+                    self.tracker.push(depth);
+                    Some(Ok(self.progress()))
+                },
+                Err(err) => {
+                    self.failed = true;
+                    Some(Err(SweepError::Parse(err)))
+                }
+            };
+        }
+    }
+}
+
+/// Counts the increases of the window sums of all readings in `reader`, see [Sweep]
+pub fn count_increases<R: BufRead>(reader: R, window_size: usize) -> Result<u64, SweepError> {
+    Sweep::new(reader, window_size).finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, Read};
+
+    use super::*;
+    use crate::memory;
+
+    /// Produces `count` readings without ever holding them all
+    struct Readings {
+        next: u64,
+        count: u64,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Readings {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() && self.next < self.count {
+                // goes up and down, so there is something to count
+                self.pending = format!("{}\n", 1000 + (self.next * 7919) % 101).into_bytes();
+                self.next += 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_vec_version() {
+        for input in [include_str!("testinput.txt"), include_str!("input.txt")] {
+            let data: Vec<u64> = input.lines().map(|l| l.trim().parse().unwrap()).collect();
+            for window_size in [1, 3, 5] {
+                let streamed = count_increases(Cursor::new(input), window_size).unwrap();
+                assert_eq!(streamed, super::super::task2_functional(&data, window_size));
+            }
+        }
+
+        let progress: Vec<Progress> = Sweep::new(Cursor::new("199\n200\n\n208\n"), 1).map(Result::unwrap).collect();
+        assert_eq!(progress.last(), Some(&Progress { readings: 3, increases: 2 }));
+    }
+
+    #[test]
+    fn stops_at_the_first_bad_line() {
+        let mut sweep = Sweep::new(Cursor::new("199\n200\n\n2x8\n210\n"), 1);
+        assert!(sweep.next().unwrap().is_ok());
+        assert!(sweep.next().unwrap().is_ok());
+        match sweep.next() {
+            Some(Err(SweepError::Parse(err))) => assert_eq!(err.line, 4),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(sweep.next().is_none());
+    }
+
+    #[test]
+    fn memory_does_not_grow_with_the_input() {
+        assert!(memory::enable());
+        let readings = |count| BufReader::new(Readings { next: 0, count, pending: Vec::new() });
+
+        let (small, small_usage) = memory::measure(|| count_increases(readings(1_000), 3).unwrap());
+        let (large, large_usage) = memory::measure(|| count_increases(readings(200_000), 3).unwrap());
+        assert!(large > small);
+        // the reader's and tracker's buffers, a few pending lines; nothing proportional to the input
+        assert_eq!(large_usage.peak, small_usage.peak);
+        assert!(large_usage.peak < 16 * 1024);
+    }
+}
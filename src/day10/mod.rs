@@ -1,3 +1,10 @@
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
+use crate::parse_error::ParseError;
+use crate::parser::{chars, map, Section};
+use crate::solution::{Answer, Solution, SolveError};
+
 /// List of sorted open brackets
 const OPEN_BRACKETS: [char; 4] =    ['(', '{', '<', '['];
 /// List of sorted closing brackets (relative to OPEN_BRACKETS)
@@ -31,7 +38,7 @@ pub fn syntax_error_to_points_task_2(error: char) -> u64 {
 
 /// Returns the first character to be found which is a syntax error
 /// if no syntax error is found, `None` is returned
-pub fn find_illegal_character(data: &String) -> Option<char> {
+pub fn find_illegal_character(data: &str) -> Option<char> {
 
         // we store all found brackets in a stack
         let mut stack: Vec<char> = Vec::new();
@@ -63,7 +70,7 @@ pub fn find_illegal_character(data: &String) -> Option<char> {
 /// (reversed stack)
 ///
 /// This works like the function above except it doesn't expect any syntax errors
-pub fn return_expected_closing_brackets(data: &String) -> Vec<char> {
+pub fn return_expected_closing_brackets(data: &str) -> Vec<char> {
     let mut stack: Vec<char> = Vec::new();
     for char in data.chars() {
         if OPEN_BRACKETS.contains(&char) {
@@ -86,42 +93,77 @@ pub fn return_expected_closing_brackets(data: &String) -> Vec<char> {
         CLOSING_BRACKETS[index_of(&OPEN_BRACKETS, *c).unwrap()]).collect();
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 10 task 1");
+pub struct Day10;
 
-    // sum the errors for each line
-    let mut error_sum = 0 as u64;
-    for line in data {
-        if let Some(error) = find_illegal_character(line) {
-            error_sum += syntax_error_to_points_task_2(error);
-        }
-        // println!("{:?}", find_illegal_character(line));
+impl Solution for Day10 {
+    type Input = Vec<String>;
+
+    fn day(&self) -> u8 {
+        10
     }
-    return error_sum;
-}
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 10 task 2");
+    fn title(&self) -> &'static str {
+        "Syntax Scoring"
+    }
+
+    /// every line is one chunk of brackets
+    fn parse(&self, lines: &[String]) -> Result<Vec<String>, ParseError> {
+        let is_bracket = |c: char| OPEN_BRACKETS.contains(&c) || CLOSING_BRACKETS.contains(&c);
+        Section::whole(lines).each_line(map(chars("one of ({<[]>})", |c| is_bracket(c).then_some(c)), String::from_iter))
+    }
+
+    fn part1(&self, data: &Vec<String>) -> Result<Answer, SolveError> {
+
+        // sum the errors for each line
+        let mut error_sum = 0_u64;
+        for line in data {
+            if let Some(error) = find_illegal_character(line) {
+                error_sum += syntax_error_to_points_task_1(error);
+            }
+            // println!("{:?}", find_illegal_character(line));
+        }
+        Ok(error_sum.into())
+    }
 
-    // filter out all lines that have errors (we do not care for them)
-    let incomplete_lines= data
-        .iter()
-        .filter(|line| find_illegal_character(line).is_none()).
-        collect::<Vec<&String>>();
+    fn part2(&self, data: &Vec<String>) -> Result<Answer, SolveError> {
 
-    // Store each error score as a member in this array
-    let mut errors: Vec<u64> = Vec::new();
-    for line in incomplete_lines {
-        let expected_brackets = return_expected_closing_brackets(line);
-        let sum = expected_brackets
+        // filter out all lines that have errors (we do not care for them)
+        let incomplete_lines= data
             .iter()
-            .fold(0 as u64, |p, c| p * 5 + syntax_error_to_points_task_2(*c));
-        errors.push(sum);
+            .filter(|line| find_illegal_character(line).is_none()).
+            collect::<Vec<&String>>();
+
+        // Store each error score as a member in this array
+        let mut errors: Vec<u64> = Vec::new();
+        for line in incomplete_lines {
+            let expected_brackets = return_expected_closing_brackets(line);
+            let sum = expected_brackets
+                .iter()
+                .fold(0_u64, |p, c| p * 5 + syntax_error_to_points_task_2(*c));
+            errors.push(sum);
+        }
+
+        // sort the array
+        errors.sort();
+
+        // ... and return the median element
+        Ok((*errors.get(errors.len() / 2).unwrap()).into())
     }
+}
 
-    // sort the array
-    errors.sort();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // ... and return the median element
-    return *errors.get(errors.len() / 2).unwrap();
+    #[test]
+    fn finds_the_first_index() {
+        assert_eq!(index_of(&['(', '[', '{', '<'], '{'), Some(2));
+        assert_eq!(index_of(&[1, 2, 2], 2), Some(1));
+    }
+
+    #[test]
+    fn returns_none_if_missing() {
+        assert_eq!(index_of(&['(', '['], '>'), None);
+        assert_eq!(index_of::<char>(&[], '('), None);
+    }
 }
@@ -1,72 +1,52 @@
-const NEIGHBOURS: [(i8, i8); 8] = [
-    (0, -1),
-    (1, 0),
-    (-1, 0),
-    (0, 1),
-    (-1, -1),
-    (-1, 1),
-    (1, -1),
-    (1, 1),
-];
-
-#[derive(Debug)]
-struct Floor {
-    matrix: Vec<Vec<u64>>
+use crate::grid::Grid;
+use crate::parse_error::ParseError;
+use crate::solution::{Answer, Solution, SolveError};
+
+#[derive(Debug, Clone)]
+pub struct Floor {
+    energy: Grid<u64>
 }
 
 impl Floor {
-    /// Reads the lines as a height x width matrix
-    fn from_input_lines(lines: &Vec<String>) -> Self {
-        let matrix = lines
-            .iter()
-            .map(|line| line.chars()
-                // this is a trick to transform the character (ASCII POS)
-                // to its actual value ('0' -> 0)
-                .map(|c| (c as u64 - '0' as u64) as u64)
-                .collect())
-            .collect::<Vec<Vec<u64>>>();
-
-        return Floor {
-            matrix
-        }
+    /// Reads the lines as a height x width matrix of digits
+    fn from_input_lines(lines: &[String]) -> Result<Self, ParseError> {
+        Ok(Floor {
+            energy: Grid::from_digits(lines)?
+        })
     }
 
     /// Gets a single element (n, m) if it exists
     pub fn get(&self, x: usize, y: usize) -> Option<&u64> {
-        self.matrix.get(y).unwrap().get(x)
+        self.energy.get(x, y)
     }
 
     /// Gets a single element (n, m) if it exists
     pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut u64> {
-        self.matrix.get_mut(y).unwrap().get_mut(x)
+        self.energy.get_mut(x, y)
     }
 
     /// width of the matrix (m)
     pub fn width(&self) -> usize {
-        self.matrix.get(0).unwrap().len()
+        self.energy.width()
     }
 
     /// height of the matrix
     pub fn height(&self) -> usize {
-        self.matrix.len()
+        self.energy.height()
     }
 
     /// increases each entry by one
     fn increase_all_by_one(&mut self) {
-        for row in self.matrix.iter_mut() {
-            for entry in row.iter_mut() {
-                *entry += 1;
-            }
+        for entry in self.energy.values_mut() {
+            *entry += 1;
         }
     }
 
     /// sets back all flashed entries to 0
     fn reset_flashed(&mut self) {
-        for row in self.matrix.iter_mut() {
-            for entry in row.iter_mut() {
-                if *entry > 9 {
-                    *entry = 0;
-                }
+        for entry in self.energy.values_mut() {
+            if *entry > 9 {
+                *entry = 0;
             }
         }
     }
@@ -83,21 +63,11 @@ impl Floor {
                 // check if the energy is > 9 and check if the position didn't already flash
                 if  !flashed.contains(&index) && self.get(x, y).unwrap() > &9 {
                     // remember this flash
-                    flashed.push((x as usize, y as usize));
-                    // iterate over all neighbours
-                    for neighbour in NEIGHBOURS {
-                        let x1 = neighbour.0 + x as i8;
-                        let y1 = neighbour.1 + y as i8;
-
-                        // Check if that neighbour exists (boundaries)
-                        if !(x1 >= 0 && y1 >= 0 && x1 < self.width() as i8 && y1 < self.height() as i8) {
-                            continue;
-                        }
-
-                        // increase the neighbours' energy by 1
-                        if let Some(val) = self.get_mut(x1 as usize, y1 as usize) {
-                            *val += 1;
-                        }
+                    flashed.push((x, y));
+                    // increase the energy of all neighbours by 1 (the grid only gives us existing ones)
+                    let neighbours: Vec<(usize, usize)> = self.energy.neighbours8(x, y).collect();
+                    for (x1, y1) in neighbours {
+                        self.energy[(x1, y1)] += 1;
                     }
                 }
             }
@@ -178,16 +148,32 @@ impl Floor {
     }
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 11 task 1");
-    let mut floor = Floor::from_input_lines(data);
-    let flashed = floor.play_n_rounds(100);
-    return flashed.iter().fold(0, |p, n| p + n.len() as u64);
-}
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Floor;
+
+    fn day(&self) -> u8 {
+        11
+    }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 11 task 2");
-    let mut floor = Floor::from_input_lines(data);
-    let round = floor.play_until_all_flash();
-    return round;
+    fn title(&self) -> &'static str {
+        "Dumbo Octopus"
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<Floor, ParseError> {
+        Floor::from_input_lines(lines)
+    }
+
+    fn part1(&self, input: &Floor) -> Result<Answer, SolveError> {
+        let mut floor = input.clone();
+        let flashed = floor.play_n_rounds(100);
+        Ok(flashed.iter().fold(0, |p, n| p + n.len() as u64).into())
+    }
+
+    fn part2(&self, input: &Floor) -> Result<Answer, SolveError> {
+        let mut floor = input.clone();
+        let round = floor.play_until_all_flash();
+        Ok(round.into())
+    }
 }
@@ -1,158 +1,106 @@
-use std::collections::HashMap;
+use crate::graph::{Graph, NodeId, Walk};
+use crate::parse_error::ParseError;
+use crate::parser::{chars, map, pair, verify, Section};
+use crate::solution::{Answer, Solution, SolveError};
 
-/// Stores all connections as entries in a map as in 'a' -> C, D, end
+/// Stores all caves and the passages between them
 #[derive(Debug)]
-struct Cave {
-    edges: HashMap<String, Vec<String>>
+pub struct Cave {
+    graph: Graph<String>,
+    /// big caves (upper case names) may be visited any number of times
+    big: Vec<bool>,
+    start: NodeId,
 }
 
 impl Cave {
-    pub fn from_input(lines: &Vec<String>) -> Self {
-        let mut edges : HashMap<String, Vec<String>> = HashMap::new();
-
-        for line in lines {
-            // split the line in left an right part
-            let splits = line.split('-').collect::<Vec<_>>();
-            let left = *splits.get(0).unwrap();
-            let right = *splits.get(1).unwrap();
-
-            // if we didn't see that node name, we create an empty vector of nodes it points to
-            if !edges.contains_key(left) {
-                edges.insert(String::from(left), vec!());
-            }
-            if !edges.contains_key(right) {
-                edges.insert(String::from(right), vec!());
-            }
-
-            // add the connection from left -> right
-            let left_node = edges.get_mut(left).unwrap();
-            left_node.push(String::from(right));
-
-            // and add a inverse connection (we can flow in both directions)
-            let right_node = edges.get_mut(right).unwrap();
-            right_node.push(String::from(left));
+    pub fn from_input(lines: &[String]) -> Result<Self, ParseError> {
+        let mut graph: Graph<String> = Graph::new();
+
+        // every line connects two caves (their names are just letters), we can walk in both directions.
+        // Two big caves next to each other could be walked back and forth forever, so at least one has to be small
+        let is_big = |name: &str| name.starts_with(|c: char| c.is_ascii_uppercase());
+        let cave = || map(chars("<cave>", |c| c.is_ascii_alphabetic().then_some(c)), String::from_iter);
+        let passage = verify(pair("-", "<cave>-<cave>", cave(), cave()), "<cave>-<cave> with at least one small cave",
+                             |(left, right): &(String, String)| !(is_big(left) && is_big(right)));
+        for (left, right) in Section::whole(lines).each_line(passage)? {
+            graph.connect(left, right, 1);
         }
 
-        return Self {
-            edges
-        }
-    }
+        // we can't walk anywhere if we don't know where to start
+        let start = graph.id("start").ok_or(ParseError::end_of_input(lines.len(), "start-<cave>"))?;
+        let big = graph.ids().map(|id| is_big(graph.node(id))).collect();
 
-    /// task 1 version
-    /// This function keeps track on where it came from ([current_track]) and which paths where
-    /// already found in [found_paths]
-    /// [from] dictates from which node / cave we want to start searching
-    fn follow_track<'a>(&'a self, from: &'a String,
-                    found_paths: &mut Vec<Vec<&'a String>>,
-                    current_track: &mut Vec<&'a String>) {
-
-        // append the current from-node to the path
-        current_track.push(from);
-        // found an end-node?
-        if from == "end" {
-            found_paths.push(current_track.clone())
-        }
+        Ok(Self {
+            graph,
+            big,
+            start,
+        })
+    }
 
-        // iterate over all paths
-        for neighbour in self.edges.get(from.into()).unwrap() {
-            // extract the first char of the caves' name
-            let first_char = neighbour.chars().take(1).last().unwrap();
-
-            // Check if we can visit that cave (node)
-            // that is if it is a upper case letter or we didn't already see it
-            if (first_char as u8) < ('a' as u8) || !current_track.contains(&neighbour) {
-                // recursively follow from the neighbour
-                self.follow_track(neighbour, found_paths, current_track);
-                // remove what we added here before we go back
-                current_track.pop();
+    /// Counts the paths from `start` to `end`. Small caves may be visited only once,
+    /// except (if [allow_two_times_visit]) a single one of them which may be visited twice.
+    /// `start` is never entered again
+    pub fn count_paths(&self, allow_two_times_visit: bool) -> u64 {
+        let end = match self.graph.id("end") {
+            Some(end) => end,
+            None => return 0,
+        };
+
+        // every walk remembers whether it already visited some small cave twice
+        self.graph.count_paths_with_state(self.start, end, false, |walk: &Walk, used_double_visit, next| {
+            if next == self.start {
+                None
+            } else if self.big[next] || walk.visits(next) == 0 {
+                Some(used_double_visit)
+            } else if allow_two_times_visit && !used_double_visit {
+                Some(true)
+            } else {
+                None
             }
-        }
+        })
     }
-    /// task 2 version
-    /// this is like the task 1 version except it remembers additionally if on the current track
-    /// there has allready been a cave with a small letter visited twice
-    fn follow_track_task_2<'a>(&'a self, from: &'a String,
-                    found_paths: &mut Vec<Vec<&'a String>>,
-                    current_track: &mut Vec<&'a String>,
-                    mut already_double_visit: &bool,
-    ) {
-
-        // found an end-node?
-        current_track.push(from);
-        if from == "end" {
-            found_paths.push(current_track.clone());
-        }
+}
 
-        for neighbour in self.edges.get(from.into()).unwrap() {
-            let first_char = neighbour.chars().take(1).last().unwrap();
-
-            // Check if we can visit that cave (node)
-            // that is if it is a upper case letter or we didn't already see it
-            // OR (task 2) if it is already double visited but we only visited one small guy
-            // exactly twice
-            let is_big_char = (first_char as u8) < ('a' as u8);
-            let mut can_visit = neighbour != "start";
-            let mut did_double_visit = false; // remember if we did a double visit this turn
-            if !is_big_char && can_visit {
-                if current_track.contains(&neighbour) {
-                    if !already_double_visit { // allow exactly one double visit
-                        already_double_visit = &true;
-                        did_double_visit = true;
-                    } else {
-                        can_visit = false;
-                    }
-                }
-            }
+pub struct Day12;
 
-            if can_visit {
-                self.follow_track_task_2(neighbour, found_paths, current_track, already_double_visit);
-                // remove what we added here before we go back
-                current_track.pop();
+impl Solution for Day12 {
+    type Input = Cave;
 
-                // Toggle the double visit flag back if we remove the double visited element
-                if did_double_visit {
-                    already_double_visit = &false;
-                }
-            }
-        }
+    fn day(&self) -> u8 {
+        12
     }
 
-    /// This initiates the process
-    /// by just initializing the data structures and calling
-    /// the [follow_track]-functions
-    /// :param allow_two_times_visit will initiate the task if true otherwise task 1
-    pub fn gather_paths(&self, allow_two_times_visit: bool) -> Vec<Vec<&String>> {
-        let mut paths : Vec<Vec<&String>> =  Vec::new();
-        paths.reserve(200_000);
-        let mut current_track = Vec::<&String>::new();
-
-        if !allow_two_times_visit {
-            self.follow_track(self.edges.keys().find(|k| *k == "start").unwrap(),
-                              &mut paths,
-                              &mut current_track);
-        } else {
-            self.follow_track_task_2(self.edges.keys().find(|k| *k == "start").unwrap(),
-                              &mut paths,
-                              &mut current_track,
-                                     &false);
-        }
+    fn title(&self) -> &'static str {
+        "Passage Pathing"
+    }
 
-        paths
+    fn parse(&self, lines: &[String]) -> Result<Cave, ParseError> {
+        Cave::from_input(lines)
+    }
+
+    fn part1(&self, cave: &Cave) -> Result<Answer, SolveError> {
+        Ok(cave.count_paths(false).into())
     }
-}
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 12 task 1");
-    let cave = Cave::from_input(data);
-    let paths = cave.gather_paths(false);
-    // println!("{:?}, \n {:?}" , cave, paths);
-    return paths.len() as u64;
+    fn part2(&self, cave: &Cave) -> Result<Answer, SolveError> {
+        Ok(cave.count_paths(true).into())
+    }
 }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 12 task 2");
-    let cave = Cave::from_input(data);
-    let paths = cave.gather_paths(true);
-    // println!("{:?}, \n {:?}" , cave, paths);
-    return paths.len() as u64;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_passages_between_big_caves() {
+        let to_lines = |lines: &[&str]| lines.iter().map(|line| String::from(*line)).collect::<Vec<String>>();
+        let err = Cave::from_input(&to_lines(&["start-A", "A-b", "A-B", "B-end"])).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.expected, "<cave>-<cave> with at least one small cave");
+
+        // a big cave next to small ones is fine
+        let cave = Cave::from_input(&to_lines(&["start-A", "A-b", "b-end"])).unwrap();
+        assert_eq!(cave.count_paths(false), 1);
+        assert_eq!(cave.count_paths(true), 2);
+    }
 }
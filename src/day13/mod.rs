@@ -1,163 +1,145 @@
-use std::cmp::max;
-use std::collections::HashSet;
-use crate::day4::parse_line_of_numbers;
+use crate::sparse_grid::{OutOfRange, Point, PointSet};
+use crate::log::{debug, info};
+use crate::parse_error::ParseError;
+use crate::parser::{self, integer, keyword, map, pair, prefixed, Parser};
+use crate::solution::{Answer, Solution, SolveError};
 
 /// Represents a instruction to be applied on a Sheet
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Instruction {
-    AlongY(usize),
-    AlongX(usize),
+    AlongY(i64),
+    AlongX(i64),
 }
 
-impl Instruction {
-    pub fn from_line(line: &String) -> Self {
+/// The axes as they are written in the input
+const AXES: [(&str, char); 2] = [("x", 'x'), ("y", 'y')];
 
-        // split at the equals sign (rightmost part will be the number)
-        let parts = line.split("=").collect::<Vec<_>>();
-        let number = (*parts.last().unwrap()).parse::<usize>().unwrap();
-
-        // split the remaining string before `=` on a space.
-        // the rightmost part there will be x or y (axis)
-        let axis = parts.first().unwrap().split(" ").last().unwrap();
-
-        if axis == "y" {
-            return Self::AlongY(number)
-        } else if axis == "x" {
-            return Self::AlongX(number);
-        }
+/// `fold along <x|y>=<position>`
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    let fold = pair("=", "<x|y>=<position>", keyword(&AXES), integer("<position>"));
+    map(prefixed("fold along ", fold), |(axis, position)| match axis {
+        'x' => Instruction::AlongX(position),
+        _ => Instruction::AlongY(position),
+    })
+}
 
-        panic!("Couldn't parse instruction");
-    }
+/// a marker line like `6,10`
+fn marker<'a>() -> impl Parser<'a, Point> {
+    pair(",", "x,y", integer("<x>"), integer("<y>"))
 }
 
-/// saves a sheet in terms of marked positions [sheet]
+/// saves a sheet in terms of marked positions [sheet] (which may be negative or huge)
 /// and a set of instructions
-#[derive(Debug)]
-struct Sheet {
-   sheet: HashSet<(usize, usize)>,
+#[derive(Debug, Clone)]
+pub struct Sheet {
+   sheet: PointSet,
    instructions: Vec<Instruction>,
 }
 
 impl Sheet {
-    pub fn from_input(lines: &Vec<String>) -> Self {
-
-        // the input is read in a stateful machine
-        // we have a state for markers (first part) and a set of instructions
-        // (second part; separated by an empty line)
-        let mut read_instructions = false;
-        let mut sheet = HashSet::<(usize, usize)>::new();
-        let mut instructions = Vec::<Instruction>::new();
-        for line in lines {
-
-            if !read_instructions {
-                // read a marker line
-                if line != "" {
-                    let numbers = parse_line_of_numbers::<usize>(line, ',');
-                    sheet.insert((*numbers.get(0).unwrap(), *numbers.get(1).unwrap()));
-                } else {
-                    // an empty line triggers a switch to the instruction mode
-                    read_instructions = true;
-                }
-            } else {
-                // read instructions
-                instructions.push(Instruction::from_line(line));
-            }
-        }
+    pub fn from_input(lines: &[String]) -> Result<Self, ParseError> {
+
+        // the markers come first, the instructions follow after an empty line
+        let sections = parser::exact_sections(lines, &["x,y", "fold along <x|y>=<position>"])?;
+        let sheet = sections[0].each_line(marker())?.into_iter().collect();
+        let instructions = sections[1].each_line(instruction())?;
 
-        Self {
+        Ok(Self {
             sheet,
             instructions
-        }
-    }
-
-    /// finds the highest x value
-    fn max_x(&self) -> usize {
-        self.sheet.iter().fold(0 as usize, |x, marker| max(x, marker.0))
+        })
     }
 
-    /// finds the highest y value
-    fn max_y(&self) -> usize {
-        self.sheet.iter().fold(0 as usize, |x, marker| max(x, marker.1))
+    /// draws the sheet (its bounding box) as rows of `#` (marked) and ` ` (empty)
+    fn render(&self) -> Vec<String> {
+        self.sheet.render_marks()
     }
 
-    /// echo the sheet to the terminal
+    /// echo the sheet to the terminal (on debug level)
     fn print(&self) {
-        for y in 0..=self.max_y() {
-           for x in 0..=self.max_x() {
-               let p = (x, y);
-               if self.sheet.contains(&p) {
-                   print!("#")
-               } else {
-                   print!(" ")
-               }
-           }
-            println!();
+        for row in self.render() {
+            debug!("{}", row);
         }
     }
 
-    /// apply a instruction at index [index]
-    fn apply_instruction(&mut self, index: usize) {
-        let instruction = self.instructions.get(index).unwrap();
-        match instruction {
-            Instruction::AlongY(position) => {
-                // find all entries below the y fold
-                let after_fold = self.sheet
-                    .iter()
-                    .filter(|marker| {
-                        return marker.1 > *position
-                    }).collect::<HashSet<_>>()
-                    .iter()
-                    .map(|i| **i).collect::<HashSet<_>>();
-
-                // remove all below the fold
-                self.sheet.retain(|marker| !after_fold.contains(marker));
-
-                // merge all below fold to the upper part of the sheet
-                // this is done by subtracting the distance to the fold from the original
-                // y position
-                after_fold.iter().for_each(|marker| {
-                   self.sheet.insert((marker.0, *position - (marker.1 - *position)));
-                });
-            }
-            Instruction::AlongX(position) => {
-                // works the same as above except the roles of x and y are changed
-                let after_fold = self.sheet
-                    .iter()
-                    .filter(|marker| {
-                        return marker.0 > *position
-                    }).collect::<HashSet<_>>()
-                    .iter()
-                    .map(|i| **i).collect::<HashSet<_>>();
-
-                self.sheet.retain(|marker| !after_fold.contains(marker));
-                after_fold.iter().for_each(|marker| {
-                    self.sheet.insert((*position - (marker.0 - *position), marker.1 ));
-                });
-            }
-        }
+    /// apply a instruction at index [index]:
+    /// all markers behind the fold are mirrored onto the other half.
+    /// Fails if a marker would land outside of the `i64` plane (the sheet is left empty then)
+    fn apply_instruction(&mut self, index: usize) -> Result<(), OutOfRange> {
+        let sheet = std::mem::take(&mut self.sheet);
+        self.sheet = match self.instructions.get(index).unwrap() {
+            Instruction::AlongY(position) => sheet.fold_up(*position)?,
+            Instruction::AlongX(position) => sheet.fold_left(*position)?,
+        };
+        Ok(())
     }
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 13 task 1");
-    let mut sheet = Sheet::from_input(data);
-    println!("Sheet before {}", sheet.sheet.len());
-    sheet.apply_instruction(0);
-    // sheet.apply_instruction(1);
-    let markers = sheet.sheet.len() as u64;
-    // println!("Sheet {:?}", sheet);
-    return markers as u64;
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Sheet;
+
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "Transparent Origami"
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<Sheet, ParseError> {
+        Sheet::from_input(lines)
+    }
+
+    fn part1(&self, input: &Sheet) -> Result<Answer, SolveError> {
+        let mut sheet = input.clone();
+        info!("Sheet before {}", sheet.sheet.len());
+        sheet.apply_instruction(0)?;
+        // sheet.apply_instruction(1);
+        let markers = sheet.sheet.len() as u64;
+        // println!("Sheet {:?}", sheet);
+        Ok(markers.into())
+    }
+
+    /// The answer are the letters drawn on the sheet after all folds
+    fn part2(&self, input: &Sheet) -> Result<Answer, SolveError> {
+        let mut sheet = input.clone();
+        info!("Sheet before {}", sheet.sheet.len());
+        for i in 0..sheet.instructions.len() {
+            sheet.apply_instruction(i)?;
+        }
+
+        sheet.print();
+        Ok(Answer::Art(sheet.render()))
+    }
 }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 13 task 2");
-    let mut sheet = Sheet::from_input(data);
-    println!("Sheet before {}", sheet.sheet.len());
-    for i in 0..sheet.instructions.len() {
-        sheet.apply_instruction(i);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_negative_and_huge_coordinates() {
+        let lines: Vec<String> = [
+            "-3,0",
+            "1000000000002,1",
+            "",
+            "fold along x=500000000000",
+        ].iter().map(|line| String::from(*line)).collect();
+        let sheet = Day13.parse(&lines).unwrap();
+
+        assert_eq!(Day13.part1(&sheet), Ok(Answer::Integer(2)));
+        assert_eq!(Day13.part2(&sheet), Ok(Answer::Art(vec![String::from("# "), String::from(" #")])));
     }
 
-    sheet.print();
-    let markers = sheet.sheet.len() as u64;
-    return markers as u64;
+    #[test]
+    fn reports_folds_off_the_plane() {
+        let lines: Vec<String> = ["9223372036854775807,0", "", "fold along x=-1"].iter().map(|line| String::from(*line)).collect();
+        let sheet = Day13.parse(&lines).unwrap();
+
+        let expected = SolveError::new("(9223372036854775807, 0) would move out of the range of i64");
+        assert_eq!(Day13.part1(&sheet), Err(expected.clone()));
+        assert_eq!(Day13.part2(&sheet), Err(expected));
+    }
 }
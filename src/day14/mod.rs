@@ -1,49 +1,43 @@
 use std::collections::HashMap;
+use crate::bignum::Count;
+use crate::parse_error::ParseError;
+use crate::parser::{self, chars, map, verify, Parser};
+use crate::solution::{Answer, Solution, SolveError};
+
+mod polymer;
+pub use polymer::{reachable_pairs, to_csv, Overflow, Polymer, StepStats};
 
 /// Stores the (current) polymer and a set of substitution rules
-#[derive(Debug)]
-struct PolymerSlow {
+#[derive(Debug, Clone)]
+pub struct PolymerSlow {
     polymer: Vec<char>,
     instructions: HashMap<(char, char), char>
 }
 
-impl PolymerSlow {
-    pub fn from_input(lines: &Vec<String>) -> Self {
-        let mut polymer = String::new();
-        let mut instructions : HashMap<(char, char), char> = HashMap::new();
-
-        for (i, line) in lines.iter().enumerate() {
-
-            // First line is the input polymer
-            if i == 0 {
-                polymer += line;
-            }
-
-            // beginning from the 3rd line we have the rules
-            if i > 1  {
-                // split the line on the arrow (notice the spaces)
-                let instruction = line.split(" -> ").collect::<Vec<_>>();
-                let from = instruction
-                    .get(0)
-                    .unwrap()
-                    .chars()
-                    .collect::<Vec<_>>();
+/// A chain of elements (upper case letters)
+fn elements<'a>(expected: &'static str) -> impl Parser<'a, Vec<char>> {
+    chars(expected, |c| c.is_ascii_uppercase().then_some(c))
+}
 
-                instructions.insert((*from.get(0).unwrap(), *from.get(1).unwrap()),
-                                    instruction.get(1).unwrap().chars().last().unwrap());
+impl PolymerSlow {
+    pub fn from_input(lines: &[String]) -> Result<Self, ParseError> {
+        // the template comes first, the rules follow after an empty line
+        let sections = parser::exact_sections(lines, &["<template>", "AB -> C"])?;
+        let polymer = sections[0].single_line("<template>", elements("<template>"))?;
 
-            }
-        }
+        let pair_of_elements = map(verify(elements("AB"), "AB", |from| from.len() == 2), |from| (from[0], from[1]));
+        let single_element = map(verify(elements("C"), "C", |to| to.len() == 1), |to| to[0]);
+        let instructions = sections[1].rules(" -> ", "AB -> C", pair_of_elements, single_element)?;
 
-        Self {
-            polymer: polymer.chars().collect(),
+        Ok(Self {
+            polymer,
             instructions
-        }
+        })
     }
 
     pub fn substitute(&mut self) {
         let polymer_new_str = self.polymer.windows(2).map(|chunk| {
-            let pair = (*chunk.get(0).unwrap(), *chunk.get(1).unwrap());
+            let pair = (*chunk.first().unwrap(), *chunk.get(1).unwrap());
             let mut res = String::from(pair.0);
             if let Some(sub) = self.instructions.get(&pair) {
                 res.push(*sub);
@@ -61,6 +55,31 @@ impl PolymerSlow {
         self.polymer = polymer_new.chars().collect();
     }
 
+    /// The same polymer and rules in the pair counting engine, which also copes with many steps
+    pub fn engine<C: Count>(&self) -> Polymer<C> {
+        Polymer::new(&self.polymer, &self.instructions)
+    }
+
+    /// Rules whose pair never shows up in a polymer grown from this template (sorted)
+    pub fn unreachable_rules(&self) -> Vec<((char, char), char)> {
+        let reachable = reachable_pairs(&self.polymer, &self.instructions);
+        let mut rules: Vec<((char, char), char)> = self.instructions
+            .iter()
+            .filter(|(pair, _)| !reachable.contains(pair))
+            .map(|(pair, inserted)| (*pair, *inserted))
+            .collect();
+        rules.sort();
+        rules
+    }
+
+    /// Pairs which show up but have no rule, [PolymerSlow::substitute] leaves them as they are (sorted)
+    pub fn pairs_without_rule(&self) -> Vec<(char, char)> {
+        reachable_pairs(&self.polymer, &self.instructions)
+            .into_iter()
+            .filter(|pair| !self.instructions.contains_key(pair))
+            .collect()
+    }
+
     /// Counts each single letter / polymer and returns their respective counts
     pub fn count_polymers(&self) -> HashMap<char, u64>{
        let mut res: HashMap<char, u64> = HashMap::new();
@@ -75,18 +94,74 @@ impl PolymerSlow {
     }
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 14 task 1");
-    let mut polymer = PolymerSlow::from_input(data);
-    for _ in 0..10 {
-        polymer.substitute();
+/// Counts which don't fit into an [Answer::Integer] are given as text
+pub fn count_answer<C: Count>(count: C) -> Answer {
+    let digits = count.to_string();
+    match digits.parse::<u64>() {
+        Ok(number) => Answer::Integer(number),
+        Err(_) => Answer::Text(digits),
     }
-    // println!("{:?}", polymer.count_polymers());
-    let counts = polymer.count_polymers();
-    return counts.values().max().unwrap() - counts.values().min().unwrap();
 }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 14 task 2");
-    return 0;
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = PolymerSlow;
+
+    fn day(&self) -> u8 {
+        14
+    }
+
+    fn title(&self) -> &'static str {
+        "Extended Polymerization"
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<PolymerSlow, ParseError> {
+        PolymerSlow::from_input(lines)
+    }
+
+    fn part1(&self, input: &PolymerSlow) -> Result<Answer, SolveError> {
+        let mut polymer = input.clone();
+        for _ in 0..10 {
+            polymer.substitute();
+        }
+        // println!("{:?}", polymer.count_polymers());
+        let counts = polymer.count_polymers();
+        Ok((counts.values().max().unwrap() - counts.values().min().unwrap()).into())
+    }
+
+    fn part2(&self, input: &PolymerSlow) -> Result<Answer, SolveError> {
+        let mut polymer: Polymer<u128> = input.engine();
+        // the polymer gets ~2^40 times longer, that's far below u128::MAX
+        polymer.steps(40).expect("40 steps fit into u128");
+        Ok(count_answer(polymer.spread().expect("40 steps fit into u128")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polymer(lines: &[&str]) -> PolymerSlow {
+        PolymerSlow::from_input(&lines.iter().map(|line| String::from(*line)).collect::<Vec<String>>()).unwrap()
+    }
+
+    #[test]
+    fn flags_unreachable_rules_and_pairs_without_rule() {
+        let polymer = polymer(&["NNC", "", "NN -> C", "NC -> B", "XY -> Z", "BB -> N"]);
+        // NN -> NC, CN; NC -> NB, BC; CN, NB and BC have no rule
+        assert_eq!(polymer.unreachable_rules(), vec![(('B', 'B'), 'N'), (('X', 'Y'), 'Z')]);
+        assert_eq!(polymer.pairs_without_rule(), vec![('B', 'C'), ('C', 'N'), ('N', 'B')]);
+
+        let example = PolymerSlow::from_input(&include_str!("testinput.txt").lines().map(String::from).collect::<Vec<String>>()).unwrap();
+        assert!(example.unreachable_rules().is_empty());
+        assert!(example.pairs_without_rule().is_empty());
+    }
+
+    #[test]
+    fn rejects_a_second_rule_for_a_pair() {
+        let lines: Vec<String> = ["NNC", "", "NN -> C", "NC -> B", "NN -> B"].iter().map(|line| String::from(*line)).collect();
+        let err = PolymerSlow::from_input(&lines).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (5, 1, "NN"));
+    }
 }
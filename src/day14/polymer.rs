@@ -0,0 +1,311 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use crate::bignum::Count;
+
+/// The counts didn't fit into the chosen integer type anymore (use a bigger one or [crate::bignum::BigUint])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    /// the step which would have overflown (1-based), `None` if counting the elements overflowed
+    pub step: Option<usize>,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.step {
+            Some(step) => write!(f, "the pair counts overflow in step {}", step),
+            None => write!(f, "the element counts overflow"),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Adds `count` to the counter of `key`
+fn add<K: Ord, C: Count>(counts: &mut BTreeMap<K, C>, key: K, count: &C) -> Result<(), ()> {
+    let counter = counts.entry(key).or_insert_with(C::zero);
+    *counter = counter.checked_add(count).ok_or(())?;
+    Ok(())
+}
+
+/// A polymer which only remembers how often every pair of neighbouring elements occurs.
+/// A step only depends on the number of distinct pairs (at most 26 * 26), not on the length of the
+/// polymer (which doubles every step), so thousands of steps are no problem as long as the
+/// counts fit into `C` (`u64` holds 64 steps of the example, `u128` twice as many, [crate::bignum::BigUint] any number)
+#[derive(Debug, Clone)]
+pub struct Polymer<C: Count = u128> {
+    pairs: BTreeMap<(char, char), C>,
+    /// the last element isn't the first one of any pair, so it is counted separately
+    last: char,
+    rules: HashMap<(char, char), char>,
+    steps: usize,
+    /// how often each rule was applied in the last step
+    fired: BTreeMap<(char, char), C>,
+}
+
+/// A snapshot of a polymer after some step, see [Polymer::evolve]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepStats<C: Count> {
+    /// 0 is the template
+    pub step: usize,
+    pub length: C,
+    pub elements: BTreeMap<char, C>,
+    pub pairs: BTreeMap<(char, char), C>,
+    /// how often each rule was applied in this step (rules which didn't fire are left out)
+    pub fired: BTreeMap<(char, char), C>,
+}
+
+impl<C: Count> Polymer<C> {
+    /// `template` must not be empty
+    pub fn new(template: &[char], rules: &HashMap<(char, char), char>) -> Self {
+        let last = *template.last().expect("a polymer needs at least one element");
+        let mut pairs = BTreeMap::new();
+        for pair in template.windows(2) {
+            // a template has far less than u64::MAX pairs
+            add(&mut pairs, (pair[0], pair[1]), &C::from_u64(1)).unwrap();
+        }
+
+        Self { pairs, last, rules: rules.clone(), steps: 0, fired: BTreeMap::new() }
+    }
+
+    /// Inserts an element into every pair which has a rule. On overflow the polymer stays as it was
+    pub fn step(&mut self) -> Result<(), Overflow> {
+        let overflow = Overflow { step: Some(self.steps + 1) };
+        let mut pairs = BTreeMap::new();
+        let mut fired = BTreeMap::new();
+        for (&(left, right), count) in &self.pairs {
+            match self.rules.get(&(left, right)) {
+                Some(&inserted) => {
+                    add(&mut pairs, (left, inserted), count).map_err(|_| overflow)?;
+                    add(&mut pairs, (inserted, right), count).map_err(|_| overflow)?;
+                    // every occurrence of the pair is one application of its rule
+                    fired.insert((left, right), count.clone());
+                },
+                None => add(&mut pairs, (left, right), count).map_err(|_| overflow)?,
+            }
+        }
+
+        self.pairs = pairs;
+        self.fired = fired;
+        self.steps += 1;
+        Ok(())
+    }
+
+    /// Does `steps` steps (stops at the first overflow)
+    pub fn steps(&mut self, steps: usize) -> Result<(), Overflow> {
+        (0..steps).try_for_each(|_| self.step())
+    }
+
+    /// How many steps were done so far
+    pub fn steps_taken(&self) -> usize {
+        self.steps
+    }
+
+    /// How often each pair of neighbours occurs
+    pub fn pair_counts(&self) -> &BTreeMap<(char, char), C> {
+        &self.pairs
+    }
+
+    pub fn rules(&self) -> &HashMap<(char, char), char> {
+        &self.rules
+    }
+
+    /// How often each rule was applied in the last step
+    pub fn rule_usage(&self) -> &BTreeMap<(char, char), C> {
+        &self.fired
+    }
+
+    /// The number of elements
+    pub fn length(&self) -> Result<C, Overflow> {
+        let overflow = Overflow { step: None };
+        self.pairs
+            .values()
+            .try_fold(C::from_u64(1), |length, count| length.checked_add(count))
+            .ok_or(overflow)
+    }
+
+    /// How often each element occurs
+    pub fn element_counts(&self) -> Result<BTreeMap<char, C>, Overflow> {
+        let overflow = Overflow { step: None };
+        let mut counts = BTreeMap::new();
+        // every element but the last starts exactly one pair
+        for (&(left, _), count) in &self.pairs {
+            add(&mut counts, left, count).map_err(|_| overflow)?;
+        }
+        add(&mut counts, self.last, &C::from_u64(1)).map_err(|_| overflow)?;
+        Ok(counts)
+    }
+
+    /// The quantity of the most common element minus the quantity of the least common one
+    pub fn spread(&self) -> Result<C, Overflow> {
+        let counts = self.element_counts()?;
+        let most = counts.values().max().unwrap();
+        let least = counts.values().min().unwrap();
+        Ok(most.minus(least))
+    }
+
+    /// Everything there is to know about the current step
+    pub fn stats(&self) -> Result<StepStats<C>, Overflow> {
+        Ok(StepStats {
+            step: self.steps,
+            length: self.length()?,
+            elements: self.element_counts()?,
+            pairs: self.pairs.clone(),
+            fired: self.fired.clone(),
+        })
+    }
+
+    /// Does `steps` steps and returns the stats of the current step and every step that follows
+    pub fn evolve(&mut self, steps: usize) -> Result<Vec<StepStats<C>>, Overflow> {
+        let mut history = vec![self.stats()?];
+        for _ in 0..steps {
+            self.step()?;
+            history.push(self.stats()?);
+        }
+        Ok(history)
+    }
+}
+
+/// The history of [Polymer::evolve] as CSV: one line per step with its length, the count of every element,
+/// every pair and how often every rule fired (`fired AB`). The columns only depend on `rules` (and pairs
+/// without a rule which are there from the start), so they stay the same no matter how many steps were done.
+/// Columns are sorted, missing counts are 0
+pub fn to_csv<C: Count>(history: &[StepStats<C>], rules: &HashMap<(char, char), char>) -> String {
+    let mut elements: BTreeSet<char> = history.iter().flat_map(|stats| stats.elements.keys().copied()).collect();
+    let mut pairs: BTreeSet<(char, char)> = history.iter().flat_map(|stats| stats.pairs.keys().copied()).collect();
+    for (&(left, right), &inserted) in rules {
+        elements.extend([left, right, inserted]);
+        pairs.extend([(left, right), (left, inserted), (inserted, right)]);
+    }
+    let rules: BTreeSet<(char, char)> = rules.keys().copied().collect();
+
+    let mut header = vec![String::from("step"), String::from("length")];
+    header.extend(elements.iter().map(|element| element.to_string()));
+    header.extend(pairs.iter().map(|(left, right)| format!("{}{}", left, right)));
+    header.extend(rules.iter().map(|(left, right)| format!("fired {}{}", left, right)));
+
+    let mut lines = vec![header.join(",")];
+    for stats in history {
+        let count = |count: Option<&C>| count.map(|c| c.to_string()).unwrap_or(String::from("0"));
+        let mut row = vec![stats.step.to_string(), stats.length.to_string()];
+        row.extend(elements.iter().map(|element| count(stats.elements.get(element))));
+        row.extend(pairs.iter().map(|pair| count(stats.pairs.get(pair))));
+        row.extend(rules.iter().map(|rule| count(stats.fired.get(rule))));
+        lines.push(row.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// All pairs which can ever occur in a polymer grown from `template`
+pub fn reachable_pairs(template: &[char], rules: &HashMap<(char, char), char>) -> BTreeSet<(char, char)> {
+    let mut reachable: BTreeSet<(char, char)> = template.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    let mut pending: Vec<(char, char)> = reachable.iter().copied().collect();
+    while let Some((left, right)) = pending.pop() {
+        if let Some(&inserted) = rules.get(&(left, right)) {
+            for pair in [(left, inserted), (inserted, right)] {
+                if reachable.insert(pair) {
+                    pending.push(pair);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bignum::BigUint;
+    use crate::day14::PolymerSlow;
+
+    fn example() -> PolymerSlow {
+        let lines: Vec<String> = include_str!("testinput.txt").lines().map(String::from).collect();
+        PolymerSlow::from_input(&lines).unwrap()
+    }
+
+    #[test]
+    fn counts_like_the_slow_polymer() {
+        let mut slow = example();
+        let mut fast: Polymer<u64> = slow.engine();
+        for _ in 0..8 {
+            slow.substitute();
+            fast.step().unwrap();
+        }
+
+        let slow_counts = slow.count_polymers();
+        let fast_counts = fast.element_counts().unwrap();
+        // `count_polymers` counts one less of each element, that doesn't change the spread
+        assert!(fast_counts.iter().all(|(element, count)| slow_counts[element] + 1 == *count));
+        assert_eq!(fast.steps_taken(), 8);
+    }
+
+    #[test]
+    fn reports_overflows_and_keeps_going_with_big_numbers() {
+        let mut small: Polymer<u64> = example().engine();
+        let err = small.steps(100).unwrap_err();
+        assert_eq!(err.step, Some(65));
+        assert_eq!(small.steps_taken(), err.step.unwrap() - 1);
+
+        let mut big: Polymer<BigUint> = example().engine();
+        big.steps(40).unwrap();
+        assert_eq!(big.spread().unwrap().to_u128(), Some(2188189693529));
+    }
+
+    #[test]
+    fn records_every_step() {
+        let mut polymer: Polymer<u64> = example().engine();
+        let history = polymer.evolve(2).unwrap();
+        assert_eq!(history.iter().map(|stats| stats.length).collect::<Vec<u64>>(), vec![4, 7, 13]);
+
+        // NCNBCHB
+        let first = &history[1];
+        assert_eq!(first.elements, BTreeMap::from([('B', 2), ('C', 2), ('H', 1), ('N', 2)]));
+        assert_eq!(first.pairs.get(&('C', 'N')), Some(&1));
+        assert_eq!(first.fired, BTreeMap::from([(('C', 'B'), 1), (('N', 'C'), 1), (('N', 'N'), 1)]));
+        assert!(history[0].fired.is_empty());
+        assert_eq!(polymer.rule_usage(), &history[2].fired);
+        assert_eq!(polymer.length(), Ok(13));
+
+        // one line per step plus the header, every line has as many columns as the header
+        let csv = to_csv(&history, polymer.rules());
+        let widths: Vec<usize> = csv.lines().map(|line| line.split(',').count()).collect();
+        assert_eq!(widths.len(), 4);
+        assert!(widths.iter().all(|width| *width == 2 + 4 + 16 + 16));
+    }
+
+    #[test]
+    fn exports_rules_which_never_fire() {
+        let rules = HashMap::from([(('N', 'N'), 'C'), (('X', 'Y'), 'Z')]);
+        let mut polymer: Polymer<u64> = Polymer::new(&['N', 'N'], &rules);
+
+        let csv = to_csv(&polymer.evolve(1).unwrap(), &rules);
+        assert_eq!(csv, "step,length,C,N,X,Y,Z,CN,NC,NN,XY,XZ,ZY,fired NN,fired XY\n\
+                         0,2,0,2,0,0,0,0,0,1,0,0,0,0,0\n\
+                         1,3,1,2,0,0,0,1,1,0,0,0,0,1,0\n");
+
+        // the columns don't depend on the number of steps
+        let longer = to_csv(&polymer.evolve(3).unwrap(), &rules);
+        assert_eq!(longer.lines().next(), csv.lines().next());
+    }
+
+    #[test]
+    fn ten_thousand_steps() {
+        let mut polymer: Polymer<BigUint> = example().engine();
+        polymer.steps(10_000).unwrap();
+
+        // every pair of the example has a rule, so the length doubles (minus one) every step: 3 * 2^10000 + 1
+        let mut expected = BigUint::from(3u64);
+        for _ in 0..10_000 {
+            let copy = expected.clone();
+            expected.add_assign(&copy);
+        }
+        expected.add_assign(&BigUint::from(1u64));
+
+        let mut length = BigUint::zero();
+        for count in polymer.element_counts().unwrap().values() {
+            length.add_assign(count);
+        }
+        assert_eq!(length, expected);
+        assert!(polymer.spread().unwrap().to_string().len() > 3000);
+    }
+}
@@ -1,6 +1,12 @@
+use crate::parse_error::ParseError;
+use crate::parser::{integer, keyword, map, pair, Parser, Section};
+use crate::solution::{Answer, Solution, SolveError};
+
+mod submarine;
+pub use submarine::{DiveError, Model, Submarine, Surfacing};
 
 ///We only have 3 commands (we cannot move backwards)
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum Command {
     Forward,
     Down,
@@ -9,107 +15,95 @@ enum Command {
 
 /// An instruction consist of a specific `Command` and a value
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     command: Command,
     value: u64
 }
 
-/// Will parse a single line from the input file to a `Command` and its value
-fn to_instruction(line: &str) -> Result<Instruction, String> {
+/// The commands as they are written in the input
+const COMMANDS: [(&str, Command); 3] = [("forward", Command::Forward), ("down", Command::Down), ("up", Command::Up)];
 
-    // We split on a whitespace
-    let split: Vec<&str> = line.split(' ').collect();
+/// Will parse a single line (like `forward 5`) from the input file to a `Command` and its value
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    map(
+        pair(" ", "<forward|down|up> <value>", keyword(&COMMANDS), integer("<value>")),
+        |(command, value)| Instruction { command, value },
+    )
+}
+
+/// Just a wrapper calling [instruction] on every line given
+fn to_instructions(data: &[String]) -> Result<Vec<Instruction>, ParseError> {
+    Section::whole(data).each_line(instruction())
+}
 
-    // ... and expect to get exactly two parts out
-    if split.len() != 2 {
-        return Err(format!("Couldn't parse line {}", line));
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Instruction>;
+
+    fn day(&self) -> u8 {
+        2
     }
 
-    // retrieve the single two parts (as string)
-    let command_str = *split.get(0).unwrap();
-    let value_str = *split.get(1).unwrap();
-
-    // Check if we understand the command
-    return if let Some(command) = match command_str {
-        "forward" => Some(Command::Forward),
-        "down" => Some(Command::Down),
-        "up" => Some(Command::Up),
-        _ => None
-    } {
-        // .. and if so try to parse the number
-        if let Ok(value) = value_str.parse::<u64>() {
-            Ok(Instruction { command, value })
-        } else {
-            Err(format!("Couldn't parse value {}", value_str))
-        }
-    } else {
-        Err(format!("Couldn't understand command {}", command_str))
+    fn title(&self) -> &'static str {
+        "Dive!"
     }
 
-}
+    fn parse(&self, lines: &[String]) -> Result<Vec<Instruction>, ParseError> {
+        to_instructions(lines)
+    }
 
-/// Just a wrapper calling [to_instruction] on every line given
-fn to_instructions(data: &Vec<String>) -> Vec<Instruction>{
-    return data.iter().map(|line| {
-        match to_instruction(line){
-            Ok(instruction) => instruction,
-            Err(msg) => panic!("{}", msg)
-        }
-    }).collect();
-}
+    /// Will do move a submarine
+    /// - parse each line into a [Instruction]
+    /// - execute the instruction (horizontal movement and depth movement)
+    ///
+    /// he redult is the product of depth and horizontal pos
+    fn part1(&self, instructions: &Vec<Instruction>) -> Result<Answer, SolveError> {
+        dive(instructions, Model::Plain)
+    }
 
-/// Will do move a submarine
-/// - parse each line into a [Instruction]
-/// - execute the instruction (horizontal movement and depth movement)
-///
-/// he redult is the product of depth and horizontal pos
-pub fn task1(data: &Vec<String>) -> u64 {
-    let instructions = to_instructions(data);
-
-    let mut horizontal_pos = 0;
-    let mut depth = 0;
-
-    for instruction in &instructions {
-        match instruction {
-            Instruction {command: Command::Up, value} => {
-                depth -= *value;
-            },
-            Instruction {command: Command::Down, value} => {
-                depth += *value;
-            },
-            Instruction {command: Command::Forward, value} => {
-                horizontal_pos += *value;
-            },
-        }
+    /// This task is basically like the first but adding an additional `aim` (like a movement vector)
+    fn part2(&self, instructions: &Vec<Instruction>) -> Result<Answer, SolveError> {
+        dive(instructions, Model::Aim)
     }
+}
 
+/// Runs the whole route from the surface. The submarine can't leave the water, so an `up` above the surface
+/// stops it at depth 0. A route leaving the range of an `i64` fails instead of panicking
+fn dive(instructions: &[Instruction], model: Model) -> Result<Answer, SolveError> {
+    let trajectory = Submarine::default().run(instructions, model, Surfacing::Clamp)?;
 
-    return horizontal_pos * depth;
+    // clamped, so neither the position nor the depth is negative
+    trajectory.last().unwrap().product()
+        .and_then(|product| u64::try_from(product).ok())
+        .map(Answer::from)
+        .ok_or(SolveError::new("position times depth doesn't fit into an i64"))
 }
 
-/// This task is basically like the first but adding an additional `aim` (like a movement vector)
-pub fn task2(data: &Vec<String>) -> u64 {
-    let instructions = to_instructions(data);
-
-    let mut horizontal_pos = 0;
-    let mut depth = 0;
-    let mut aim: u64 = 0;
-
-    for instruction in &instructions {
-        match instruction {
-            Instruction {command: Command::Up, value} => {
-                aim -= value;
-            },
-            Instruction {command: Command::Down, value} => {
-                aim += value;
-            },
-            Instruction {command: Command::Forward, value} => {
-                horizontal_pos += value;
-                depth += aim * value;
-            },
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{AnySolution, Part, RunError};
+
+    fn solve(lines: &[&str]) -> (Result<Answer, RunError>, Result<Answer, RunError>) {
+        let lines: Vec<String> = lines.iter().map(|line| String::from(*line)).collect();
+        (Day2.run(&lines, Part::One), Day2.run(&lines, Part::Two))
     }
 
+    fn failed(message: &str) -> Result<Answer, RunError> {
+        Err(RunError::Solve(SolveError::new(message)))
+    }
+
+    #[test]
+    fn surfaces_and_fails_out_of_range() {
+        // part 1: depth 0 (not -3), then 2; part 2: the aim is -1 in the end, so forward keeps it at the surface
+        assert_eq!(solve(&["forward 5", "up 3", "down 2", "forward 1"]), (Ok(Answer::Integer(12)), Ok(Answer::Integer(0))));
 
-    return horizontal_pos * depth;
+        let (part1, part2) = solve(&["down 9223372036854775807", "forward 2", "down 1"]);
+        assert_eq!(part1, failed("instruction 3 moves the submarine out of range"));
+        assert_eq!(part2, failed("instruction 2 moves the submarine out of range"));
+
+        let (part1, _) = solve(&["forward 4294967296", "down 4294967296"]);
+        assert_eq!(part1, failed("position times depth doesn't fit into an i64"));
+    }
 }
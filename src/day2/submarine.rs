@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::solution::SolveError;
+
+use super::{Command, Instruction};
+
+/// How `down`, `up` and `forward` are interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// part 1: `down` / `up` change the depth directly
+    Plain,
+    /// part 2: `down` / `up` change the aim, `forward` also dives by `aim * value`
+    Aim,
+}
+
+/// What happens if a move would take the submarine above the surface (a negative depth)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surfacing {
+    /// it stops at the surface (depth 0)
+    Clamp,
+    /// the run fails with [DiveError::AboveSurface]
+    Error,
+    /// negative depths are fine (it flies)
+    Allow,
+}
+
+/// Why a run stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiveError {
+    /// instruction `step` (1-based) would have taken the submarine to `depth`
+    AboveSurface { step: usize, depth: i64 },
+    /// instruction `step` (1-based) moved the submarine further than an `i64` reaches
+    Overflow { step: usize },
+}
+
+impl fmt::Display for DiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiveError::AboveSurface { step, depth } => {
+                write!(f, "instruction {} takes the submarine above the surface (depth {})", step, depth)
+            },
+            DiveError::Overflow { step } => write!(f, "instruction {} moves the submarine out of range", step),
+        }
+    }
+}
+
+impl std::error::Error for DiveError {}
+
+impl From<DiveError> for SolveError {
+    fn from(err: DiveError) -> Self {
+        SolveError::new(err.to_string())
+    }
+}
+
+/// Where the submarine is and where it points to. All signed, so moving up never wraps around
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Submarine {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+impl Submarine {
+    /// The state after executing `instruction` (which is the `step`th one, used for errors)
+    pub fn step(&self, instruction: &Instruction, step: usize, model: Model, surfacing: Surfacing) -> Result<Submarine, DiveError> {
+        let overflow = DiveError::Overflow { step };
+        let value = i64::try_from(instruction.value).map_err(|_| overflow)?;
+        let mut next = *self;
+
+        match (model, instruction.command) {
+            (_, Command::Forward) => {
+                next.position = self.position.checked_add(value).ok_or(overflow)?;
+                if model == Model::Aim {
+                    let dive = self.aim.checked_mul(value).ok_or(overflow)?;
+                    next.depth = self.depth.checked_add(dive).ok_or(overflow)?;
+                }
+            },
+            (Model::Plain, Command::Down) => next.depth = self.depth.checked_add(value).ok_or(overflow)?,
+            (Model::Plain, Command::Up) => next.depth = self.depth.checked_sub(value).ok_or(overflow)?,
+            (Model::Aim, Command::Down) => next.aim = self.aim.checked_add(value).ok_or(overflow)?,
+            (Model::Aim, Command::Up) => next.aim = self.aim.checked_sub(value).ok_or(overflow)?,
+        }
+
+        if next.depth < 0 {
+            match surfacing {
+                Surfacing::Clamp => next.depth = 0,
+                Surfacing::Error => return Err(DiveError::AboveSurface { step, depth: next.depth }),
+                Surfacing::Allow => {},
+            }
+        }
+        Ok(next)
+    }
+
+    /// Executes all `instructions` starting from here.
+    /// Returns every state along the way: this one first, then one per instruction
+    pub fn run(&self, instructions: &[Instruction], model: Model, surfacing: Surfacing) -> Result<Vec<Submarine>, DiveError> {
+        let mut trajectory = Vec::with_capacity(instructions.len() + 1);
+        trajectory.push(*self);
+        for (index, instruction) in instructions.iter().enumerate() {
+            let next = trajectory[index].step(instruction, index + 1, model, surfacing)?;
+            trajectory.push(next);
+        }
+        Ok(trajectory)
+    }
+
+    /// What the riddle asks for: horizontal position times depth
+    pub fn product(&self) -> Option<i64> {
+        self.position.checked_mul(self.depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day2::to_instructions;
+
+    fn instructions(lines: &[&str]) -> Vec<Instruction> {
+        to_instructions(&lines.iter().map(|line| String::from(*line)).collect::<Vec<String>>()).unwrap()
+    }
+
+    #[test]
+    fn follows_both_models() {
+        let route = instructions(&["forward 5", "down 5", "forward 8", "up 3", "down 8", "forward 2"]);
+        let plain = Submarine::default().run(&route, Model::Plain, Surfacing::Error).unwrap();
+        assert_eq!(plain.len(), 7);
+        assert_eq!(plain[0], Submarine::default());
+        assert_eq!(plain[6], Submarine { position: 15, depth: 10, aim: 0 });
+        assert_eq!(plain[6].product(), Some(150));
+
+        let aimed = Submarine::default().run(&route, Model::Aim, Surfacing::Error).unwrap();
+        assert_eq!(aimed[3], Submarine { position: 13, depth: 40, aim: 5 });
+        assert_eq!(aimed[6].product(), Some(900));
+    }
+
+    #[test]
+    fn surfacing_policies() {
+        let route = instructions(&["down 2", "up 5", "down 1"]);
+        let run = |surfacing| Submarine::default().run(&route, Model::Plain, surfacing);
+
+        assert_eq!(run(Surfacing::Error), Err(DiveError::AboveSurface { step: 2, depth: -3 }));
+        let depths = |trajectory: Vec<Submarine>| trajectory.iter().map(|state| state.depth).collect::<Vec<i64>>();
+        assert_eq!(depths(run(Surfacing::Clamp).unwrap()), vec![0, 2, 0, 1]);
+        assert_eq!(depths(run(Surfacing::Allow).unwrap()), vec![0, 2, -3, -2]);
+
+        // aiming upwards is fine, only rising above the surface counts
+        let aimed = instructions(&["up 2", "forward 3"]);
+        let trajectory = Submarine::default().run(&aimed, Model::Aim, Surfacing::Allow).unwrap();
+        assert_eq!(trajectory[2], Submarine { position: 3, depth: -6, aim: -2 });
+        assert_eq!(Submarine::default().run(&aimed[..1], Model::Aim, Surfacing::Error).unwrap()[1].aim, -2);
+
+        let far = instructions(&["forward 18446744073709551615"]);
+        assert_eq!(Submarine::default().run(&far, Model::Plain, Surfacing::Allow), Err(DiveError::Overflow { step: 1 }));
+    }
+}
@@ -1,10 +1,17 @@
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
 use std::collections::HashSet;
 use std::ops::Div;
+use crate::log::{info};
+use crate::parse_error::ParseError;
+use crate::parser::{self, integer, list, tokens};
+use crate::solution::{Answer, Solution, SolveError};
 
 /// Contains a field for the all the numbers being drawn and
 /// the linearized(!) matrices of numbers for the Bingo sheets
 #[derive(Debug)]
-struct GameWorld<T> {
+pub struct GameWorld<T> {
 
     /// These are the numbers which will be drawn "randomly" (not here, in fact)
     drawn_numbers: Vec<T>,
@@ -18,57 +25,39 @@ struct GameWorld<T> {
 
 impl<T: std::str::FromStr + Copy> GameWorld<T> {
 
-    /// parses the input and creates a field with all information contained
-    pub fn from_raw_input(data: &Vec<String>) -> GameWorld<T> {
+    /// parses the input and creates a field with all information contained.
+    /// The first section (line) contains the numbers to be drawn, every following section is one sheet
+    pub fn from_raw_input(data: &[String]) -> Result<GameWorld<T>, ParseError> {
+        let sections = parser::sections(data);
+        let (numbers, boards) = sections
+            .split_first()
+            .ok_or(ParseError::end_of_input(0, "<drawn numbers>"))?;
 
-        // First line contains the numbers to be drawn
-        let first_line = data.get(0).expect("No input given");
+        let drawn_numbers = numbers.single_line("<drawn numbers>", list(",", integer("<number>")))?;
 
-        // collects all sheets
+        // every sheet is a table of numbers aligned by (possibly several) spaces
         let mut sheets: Vec<Vec<T>> = Vec::new();
-
-        // collects the current sheet we are parsing
-        let mut curr_sheet: Vec<T> = Vec::new();
-
-        // stores the with (as in columns) of one sheet
         let mut sheet_width = 0;
+        for board in boards {
+            let sheet = board.table(tokens(' ', integer("<number>")))?;
 
-        // Skip the first two lines (drawn numbers + empty input) an read from there
-        for (i, line) in data.iter().skip(2).enumerate() {
-            // Skip all empty guys
-            if line.len() == 0 {
-                continue;
-            }
-
-            // Get all numbers
-            let mut line_split: Vec<T> = parse_line_of_numbers::<T>(line, ' ');
-
-            // Remember the sheet width (# numbers we see first)
+            // Remember the sheet width (# numbers we see first), all other sheets have to be just as wide
             if sheet_width == 0 {
-                sheet_width = line_split.len();
+                sheet_width = sheet.width();
             }
-            // Store this line (as in row) to the current sheet
-            curr_sheet.append(&mut line_split);
-
-            // try to get the next line (current index + skipped lines + 1 for next)
-            let next_line = data.get(i+2+1);
-
-            // a field is completed when we reach the end of the input
-            // or we seen an empty input
-            // if it is finished we append the sheet to the list of sheets and
-            // empty the current sheet
-            if next_line.is_none() || next_line.unwrap().len() == 0{
-                sheets.push(curr_sheet.clone());
-                curr_sheet.clear();
+            if sheet.width() != sheet_width {
+                return Err(ParseError::at_line(board.offset, &board.lines[0], &format!("{} numbers", sheet_width)));
             }
+
+            sheets.push(sheet.values().copied().collect());
         }
 
         // ... just return the gathered information
-        return Self {
-            drawn_numbers: parse_line_of_numbers::<T>(first_line, ','),
+        return Ok(Self {
+            drawn_numbers,
             sheets,
             sheet_width,
-        }
+        });
     }
 
 }
@@ -234,87 +223,131 @@ impl<'a, T: std::cmp::PartialEq + std::ops::Add<Output = T> + std::str::FromStr
 
 /// Just parses a String an splits it on [delim].
 /// Tries to parse each trimmed split into [T]
-/// Ignores empty fields and failed parse attempts
-pub fn parse_line_of_numbers<T>(line: &String, delim: char) -> Vec<T> where T: std::str::FromStr {
-    let mut data: Vec<T> = Vec::new();
-    for split in line.split(delim) {
-        if let Ok(maybe_number) = split.trim().parse::<T>() {
-            data.push(maybe_number);
-        }
-    }
-    return data;
+/// Ignores empty fields, but everything else has to be a number (the error points at the first one which isn't)
+pub fn parse_line_of_numbers<T>(line: &str, delim: char) -> Result<Vec<T>, ParseError> where T: std::str::FromStr {
+    parser::line(0, line, tokens(delim, integer("<number>")))
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 4 task 1");
-    let game_field = GameWorld::<u64>::from_raw_input(data);
-    let mut game = Bingo::new(&game_field);
-
-    // println!("{:?}", game);
-
-    // while we can draw some number
-    while game.can_draw() {
-        //... we simulate the draw
-        game.draw();
-
-        // Check if we have one winner
-        let winners = game.compute_winners();
-        if let Some(winner) = winners.get(0) {
-            // and if so get the last number drawn and the sum of all unmarked numbers of this sheet
-            let last_drawn_number = *game.field.drawn_numbers.get(game.pointer-1).unwrap();
-            let unmarked_sum = game.count_unmarked(*winner);
-            println!("Winner {}, last drawn number: {}, unmarked sum {:?}",
-                     winner,
-                     last_drawn_number,
-                     unmarked_sum
-            );
-
-            // as per definition
-            return last_drawn_number * unmarked_sum.unwrap();
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = GameWorld<u64>;
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Giant Squid"
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<GameWorld<u64>, ParseError> {
+        GameWorld::from_raw_input(lines)
+    }
+
+    fn part1(&self, game_field: &GameWorld<u64>) -> Result<Answer, SolveError> {
+        let mut game = Bingo::new(game_field);
+
+        // println!("{:?}", game);
+
+        // while we can draw some number
+        while game.can_draw() {
+            //... we simulate the draw
+            game.draw();
+
+            // Check if we have one winner
+            let winners = game.compute_winners();
+            if let Some(winner) = winners.first() {
+                // and if so get the last number drawn and the sum of all unmarked numbers of this sheet
+                let last_drawn_number = *game.field.drawn_numbers.get(game.pointer-1).unwrap();
+                let unmarked_sum = game.count_unmarked(*winner);
+                info!("Winner {}, last drawn number: {}, unmarked sum {:?}",
+                         winner,
+                         last_drawn_number,
+                         unmarked_sum
+                );
+
+                // as per definition
+                return Ok((last_drawn_number * unmarked_sum.unwrap()).into());
+            }
         }
+
+        Ok(Answer::Integer(0))
     }
 
-    return 0;
+
+    fn part2(&self, game_field: &GameWorld<u64>) -> Result<Answer, SolveError> {
+        let mut game = Bingo::new(game_field);
+
+        // Same as above
+        let mut last_winners: HashSet<usize> = HashSet::new();
+        while game.can_draw() {
+            game.draw();
+
+            // We use a set here (which is a mathematical set allowing for set unions etc)
+            // to store the winning sheets
+            let winners: HashSet<usize> = game.compute_winners().iter().cloned().collect();
+
+            // if the last sheet has one
+            if winners.len() == game.field.sheets.len() {
+                // find the sheet which is new into the game
+                let winner_as_vec = winners
+                    .difference(&last_winners) // take the difference to the last winners
+                    .cloned().collect::<Vec<usize>>();
+                // and get the (hopefully) only one winner we have new this round
+                let winner = *winner_as_vec.first().unwrap();
+
+                // do the same as in task one
+                let last_drawn_number = *game.field.drawn_numbers.get(game.pointer-1).unwrap();
+                let unmarked_sum = game.count_unmarked(winner);
+                info!("Last Winner {}, last drawn number: {}, unmarked sum {:?}",
+                         winner,
+                         last_drawn_number,
+                         unmarked_sum
+                );
+
+                return Ok((last_drawn_number * unmarked_sum.unwrap()).into());
+            } else {
+                // remember this rounds winners to compare it to the next round winners
+                last_winners = last_winners.union(&winners).cloned().collect();
+            }
+        }
+        Ok(Answer::Integer(0))
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 4 task 2");
-    let game_field = GameWorld::<u64>::from_raw_input(data);
-    let mut game = Bingo::new(&game_field);
-
-    // Same as above
-    let mut last_winners: HashSet<usize> = HashSet::new();
-    while game.can_draw() {
-        game.draw();
-
-        // We use a set here (which is a mathematical set allowing for set unions etc)
-        // to store the winning sheets
-        let winners: HashSet<usize> = game.compute_winners().iter().cloned().collect();
-
-        // if the last sheet has one
-        if winners.len() == game.field.sheets.len() {
-            // find the sheet which is new into the game
-            let winner_as_vec = winners
-                .difference(&last_winners) // take the difference to the last winners
-                .cloned().collect::<Vec<usize>>();
-            // and get the (hopefully) only one winner we have new this round
-            let winner = *winner_as_vec.get(0).unwrap();
-
-            // do the same as in task one
-            let last_drawn_number = *game.field.drawn_numbers.get(game.pointer-1).unwrap();
-            let unmarked_sum = game.count_unmarked(winner);
-            println!("Last Winner {}, last drawn number: {}, unmarked sum {:?}",
-                     winner,
-                     last_drawn_number,
-                     unmarked_sum
-            );
-
-            return last_drawn_number * unmarked_sum.unwrap();
-        } else {
-            // remember this rounds winners to compare it to the next round winners
-            last_winners = last_winners.union(&winners).cloned().collect();
-        }
+    #[test]
+    fn parses_numbers_with_a_delimiter() {
+        let numbers: Vec<u64> = parse_line_of_numbers(&String::from("7,4,9,5,11"), ',').unwrap();
+        assert_eq!(numbers, vec![7, 4, 9, 5, 11]);
+    }
+
+    #[test]
+    fn skips_empty_entries_between_repeated_delimiters() {
+        let numbers: Vec<u64> = parse_line_of_numbers(&String::from(" 8  2 23  4 24"), ' ').unwrap();
+        assert_eq!(numbers, vec![8, 2, 23, 4, 24]);
+    }
+
+    #[test]
+    fn parses_into_the_requested_type() {
+        let numbers: Vec<i32> = parse_line_of_numbers(&String::from("-1,0,1"), ',').unwrap();
+        assert_eq!(numbers, vec![-1, 0, 1]);
+    }
+
+    #[test]
+    fn rejects_tokens_which_are_no_numbers() {
+        let err = parse_line_of_numbers::<u64>(&String::from("7,4,x9,5"), ',').unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "x9"));
+    }
+
+    #[test]
+    fn rejects_sheets_of_different_width() {
+        let lines: Vec<String> = ["7,4", "", "1 2", "3 4", "", "1 2 3", "4 5 6"].iter().map(|l| String::from(*l)).collect();
+        let err = GameWorld::<u64>::from_raw_input(&lines).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (6, "2 numbers"));
     }
-    return 0;
 }
@@ -1,43 +1,63 @@
-use std::cmp::min;
-use std::cmp::max;
-use std::fmt::{Debug};
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
+use crate::sparse_grid::{Bounds, Point, SparseGrid};
+use crate::log::info;
+use crate::parse_error::ParseError;
+use crate::parser::{self, integer, map, pair, verify, Parser, Section};
+use crate::solution::{Answer, Solution, SolveError};
 
 /// Stores a "Box" (basically a line)
-/// as in (x1, y1, x3, y2)
+/// as in (x1, y1, x3, y2). Coordinates may be negative.
+/// Lines are horizontal, vertical or diagonal at exactly 45°
 #[derive(Debug)]
 #[derive(Clone)]
-struct LineSegment(u64, u64, u64, u64);
+pub struct LineSegment(i64, i64, i64, i64);
 
 
 /// Code for a line segment
 impl LineSegment {
 
-    /// factory for building a line segment given a input line as String
+    /// horizontal, vertical or 45° (the only kinds of vents the riddle knows)
+    pub fn is_straight(&self) -> bool {
+        self.0 == self.2 || self.1 == self.3 || self.0.abs_diff(self.2) == self.1.abs_diff(self.3)
+    }
+
+    /// every point from one end to the other. The direction is -1, 0 or 1 per axis, and stepping
+    /// stops at the far end, so even lines at the very edge of `i64` never overflow
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let end = (self.2, self.3);
+        let step_x = self.2.cmp(&self.0) as i64;
+        let step_y = self.3.cmp(&self.1) as i64;
+        std::iter::successors(Some((self.0, self.1)), move |&(x, y)| {
+            if (x, y) == end {
+                return None;
+            }
+            Some((x.checked_add(step_x)?, y.checked_add(step_y)?))
+        })
+    }
+
+    /// factory for building a line segment given a input line (at index `index`) as String
     /// (i.e., `x1,y1 -> x2,y2`)
-    pub fn from_input(line: &String) -> Self{
-        // separate into left and right half
-        let data: Vec<&str> = line.split(&" -> ").collect();
-
-        // get the numbers on the left
-        let x1y1 = crate::day4::parse_line_of_numbers::<u64>(
-            &String::from(*data.get(0).unwrap()),
-            ',');
-
-        // ... and on the right half
-        let x2y2 = crate::day4::parse_line_of_numbers::<u64>(
-            &String::from(*data.get(1).unwrap()),
-            ',');
-
-        // and return the struct
-        return LineSegment (
-            *x1y1.get(0).unwrap(),
-            *x1y1.get(1).unwrap(),
-            *x2y2.get(0).unwrap(),
-            *x2y2.get(1).unwrap(),
-        );
+    pub fn from_input(index: usize, line: &str) -> Result<Self, ParseError> {
+        parser::line(index, line, line_segment())
     }
 }
 
+/// `x1,y1 -> x2,y2`
+fn line_segment<'a>() -> impl Parser<'a, LineSegment> {
+    let segment = map(
+        pair(" -> ", "x1,y1 -> x2,y2", point(), point()),
+        |((x1, y1), (x2, y2))| LineSegment(x1, y1, x2, y2),
+    );
+    verify(segment, "a horizontal, vertical or 45° line", LineSegment::is_straight)
+}
+
+/// `x,y`
+fn point<'a>() -> impl Parser<'a, Point> {
+    pair(",", "x,y", integer("<x>"), integer("<y>"))
+}
+
 
 /// Describes our floor which has dangerous hot vents along lines
 #[derive(Debug)]
@@ -45,92 +65,21 @@ struct OceanFloor {
     vents: Vec<LineSegment>,
 }
 
-/// Helper function that lets you print some vector as a matrix
-/// (only works for single digit numbers atm)
-fn print_as_matrix<T: Debug>(mat: &Vec<T>, width: usize) {
-    for (i, entry) in mat.iter().enumerate() {
-        if (i % width) == 0 {
-            println!();
-        }
-        print!(" {:?} ", entry);
-    }
-    println!();
-}
-
 /// methods and functions of the [OceanFloor]
 impl OceanFloor {
 
-    /// creates a linearized matrix of the oceans' floor
-    pub fn build_scene(&self) -> Vec<u64> {
-
-        // load x-y exntend
-        let (width, height) = self.get_extends();
+    /// counts the vents on every position of the oceans' floor.
+    /// Only positions which are covered by a vent are stored, so the floor may be huge
+    pub fn build_scene(&self) -> SparseGrid<u64> {
 
-        // stores our linearized floor as numbers
-        let mut scene: Vec<u64> =
-            (0..width*height)
-            .map(|_| 0).collect::<Vec<u64>>();
+        // stores how many vents cross each position
+        let mut scene = SparseGrid::new();
 
-        // iterate over all vents
+        // iterate over all vents and count every point they cover
         for vent in &self.vents {
-            // get each vents' extend
-            let min_x = min(vent.0, vent.2);
-            let max_x = max(vent.0, vent.2);
-            let min_y = min(vent.1, vent.3);
-            let max_y = max(vent.1, vent.3);
-
-            // and calculate their x and y-spreads
-            let diff_x = max_x - min_x;
-            let diff_y = max_y - min_y;
-
-            // check which one is faster (i.e., has the greater difference)
-            if diff_x > diff_y {
-                // stores the increase in y-units per x unit
-                // this reads as move dy units into y direction when moving 1 unit into x direction
-                let mut dy: f64 = 0.0;
-                if (max_x - min_x) > 0 {
-                    // ... and is calculated as the relative speed of the smaller y-span
-                    // to the x-span
-                    dy = (vent.1 as f64 - vent.3 as f64) / (vent.0 as f64 - vent.2 as f64);
-                }
-                // get the points which have to be set
-                let mut steps: Vec<u64> = (vent.0..=vent.2).collect();
-                if vent.0 > vent.2 {
-                    // ... and invert the walking direction depending in which direction we walk
-                    steps = (vent.2..=vent.0).rev().collect();
-                    dy *= -1.0;
-                }
-                // iterate over the steps
-                for (step, x) in steps.iter().enumerate() {
-                    //... calculate the y position
-                    let y = (vent.1 as f64 + step as f64 * dy).round() as u64;
-                    // .. and do some rust-y things to set the ocean floors value' value
-                    let attr_to_set: &mut u64 = scene.get_mut((x + y * width) as usize).unwrap();
-                    *attr_to_set += 1;
-                }
-            } else {
-                // This is the code path for y-speed >= x speed
-                // i.e., here the main direction is the y - direction
-                // otherwise the code is almost identical
-                let mut dx: f64 = 0.0;
-                if (max_y - min_y) > 0 {
-                    dx = (vent.0 as f64 - vent.2 as f64) / (vent.1 as f64 - vent.3 as f64);
-                }
-                let mut steps: Vec<u64> = (vent.1..=vent.3).collect();
-                if vent.1 > vent.3 {
-                    steps = (vent.3..=vent.1).rev().collect();
-                    dx *= -1.0;
-                }
-                for (step, y) in steps.iter().enumerate() {
-                    // we could even save the multiplication by increasing each time by
-                    // dx and store the result in a separate var (mult is more expensive
-                    // and we do basic drawing algorithms here)
-                    let x = (vent.0 as f64 + step as f64 * dx).round() as u64;
-                    let attr_to_set: &mut u64 = scene.get_mut((x + y * width) as usize).unwrap();
-                    *attr_to_set += 1;
-                }
+            for point in vent.points() {
+                *scene.get_or_insert(point, 0) += 1;
             }
-
         }
 
         return scene;
@@ -139,65 +88,101 @@ impl OceanFloor {
     /// returns the tasks' result
     /// this is: counting all patches of the scene where "lines" do overlap
     pub fn calculate_overlap(&self) -> u64 {
-        return self.build_scene().iter().fold(0 as u64, |p,n| {
-            if *n > 1 as u64 {
+        return self.build_scene().values().fold(0_u64, |p,n| {
+            if *n > 1_u64 {
                 return p + 1;
             }
             return p;
         })
     }
 
-    /// factory for building the class from a list of lines optionally
+    /// factory for building the class from a list of vents optionally
     /// filters out all inputs which are diagonal lines
-    pub fn from_input(lines: &Vec<String>, filter_diagonal: bool) -> Self {
-        let mut lines: Vec<LineSegment> = lines
-            .iter()
-            .map(|line| LineSegment::from_input(line))
-            .collect();
+    pub fn from_vents(vents: &[LineSegment], filter_diagonal: bool) -> Self {
+        let mut lines: Vec<LineSegment> = vents.to_vec();
 
         if filter_diagonal {
-            lines = lines
-                .into_iter()
-                .filter(|line| {
-                    return line.0 == line.2 || line.1 == line.3;
-            })
-            .collect();
+            lines.retain(|line| {
+                return line.0 == line.2 || line.1 == line.3;
+            });
         }
         return Self{
             vents: lines
         }
     }
 
-    /// Gets the highest x and y spread
-    pub fn get_extends(&self) -> (u64, u64) {
-        let inner = self.vents
+    /// Gets the rectangle spanned by all vents (`None` without vents)
+    pub fn get_extends(&self) -> Option<Bounds> {
+        let ends: Vec<Point> = self.vents
             .iter()
-            .fold((0, 0), |prev, vent| {
-              return (
-                    max(prev.0, max(vent.0, vent.2)),
-                    max(prev.1, max(vent.1, vent.3))
-                  );
-            });
+            .flat_map(|vent| [(vent.0, vent.1), (vent.2, vent.3)])
+            .collect();
 
-        return (inner.0 + 1, inner.1 + 1)
+        return Bounds::of(&ends);
     }
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<LineSegment>;
+
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "Hydrothermal Venture"
+    }
+
+    /// every line is one vent
+    fn parse(&self, lines: &[String]) -> Result<Vec<LineSegment>, ParseError> {
+        Section::whole(lines).each_line(line_segment())
+    }
+
+    fn part1(&self, vents: &Vec<LineSegment>) -> Result<Answer, SolveError> {
+        let ocean = OceanFloor::from_vents(vents, true);
+        let overlap = ocean.calculate_overlap();
+        info!("extends: {:?}, overlap: {}", ocean.get_extends(), overlap);
+        Ok(overlap.into())
+    }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 5 task 1");
-    let ocean = OceanFloor::from_input(data, true);
-    let overlap = ocean.calculate_overlap();
-    println!("extends: {:?}, overlap: {}", ocean.get_extends(), overlap);
-    return overlap;
+
+    fn part2(&self, vents: &Vec<LineSegment>) -> Result<Answer, SolveError> {
+        let ocean = OceanFloor::from_vents(vents, false);
+        let overlap = ocean.calculate_overlap();
+        info!("extends: {:?}, overlap: {}", ocean.get_extends(), overlap);
+        Ok(overlap.into())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn works_with_huge_and_negative_coordinates() {
+        let lines: Vec<String> = [
+            // beyond 2^53, where neighbouring coordinates are the same f64
+            "9007199254740993,9007199254740993 -> 9007199254740997,9007199254740997",
+            "9007199254740992,9007199254740995 -> 9007199254740998,9007199254740995",
+            "9007199254740994,9007199254740990 -> 9007199254740994,9007199254741000",
+            // at the very edge of i64
+            "9223372036854775807,-9223372036854775808 -> 9223372036854775800,-9223372036854775801",
+            "9223372036854775803,-9223372036854775808 -> 9223372036854775803,-9223372036854775800",
+        ].iter().map(|line| String::from(*line)).collect();
+        let vents = Day5.parse(&lines).unwrap();
+        assert_eq!(vents[3].points().count(), 8);
+
+        // only the horizontal and the vertical line beyond 2^53 cross
+        assert_eq!(Day5.part1(&vents), Ok(Answer::Integer(1)));
+        // the diagonals cross both of them and the vertical line at the edge
+        assert_eq!(Day5.part2(&vents), Ok(Answer::Integer(4)));
+    }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 5 task 2");
-    let ocean = OceanFloor::from_input(data, false);
-    // print_matrix(&ocean.build_scene(), ocean.get_extends().0 as usize);
-    let overlap = ocean.calculate_overlap();
-    println!("extends: {:?}, overlap: {}", ocean.get_extends(), overlap);
-    return overlap;
+    #[test]
+    fn rejects_crooked_lines() {
+        let err = Day5.parse(&[String::from("0,0 -> 8,4")]).unwrap_err();
+        assert_eq!(err.expected, "a horizontal, vertical or 45° line");
+    }
 }
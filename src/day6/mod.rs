@@ -1,24 +1,31 @@
-use crate::day4::parse_line_of_numbers;
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
+use crate::parse_error::ParseError;
+use crate::parser::{self, integer, list, verify};
+use crate::solution::{Answer, Solution, SolveError};
 
 /// Stores how many days the fishes need to reproduce
-#[derive(Debug)]
-struct SeaOfFishes {
+#[derive(Debug, Clone)]
+pub struct SeaOfFishes {
     fish_reproductions: [u64; 9],
 }
 
 impl SeaOfFishes {
-    fn from_input(line: &String) -> Self {
-        let line = parse_line_of_numbers::<u64>(line, ',');
-        let mut fishes = [0 as u64; 9];
+    fn from_input(line: &str) -> Result<Self, ParseError> {
+        let mut fishes = [0_u64; 9];
+
+        // a fish can't be further than 8 days away from reproducing
+        let days = verify(integer::<usize>("<days 0-8>"), "<days 0-8>", |days| *days <= 8);
 
         // add each fishes' current reproduction days (init)
-        for number in line {
-            fishes[number as usize] += 1;
+        for days in parser::line(0, line, list(",", days))? {
+            fishes[days] += 1;
         }
 
-        return SeaOfFishes {
+        return Ok(SeaOfFishes {
             fish_reproductions: fishes
-        }
+        });
     }
 
     /// Lets one day pass
@@ -28,7 +35,7 @@ impl SeaOfFishes {
         let reproducing_fishes = self.fish_reproductions[0];
 
         // Move each fish one slot (day) forwars
-        for i in 0 as usize..=7 {
+        for i in 0_usize..=7 {
             self.fish_reproductions[i] = self.fish_reproductions[i+1];
         }
 
@@ -52,16 +59,33 @@ impl SeaOfFishes {
     }
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 6 task 2");
-    let mut field = SeaOfFishes::from_input(data.get(0).unwrap());
-    field.tick_n_days(80);
-    return field.count_the_pool();
-}
+pub struct Day6;
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 6 task 2");
-    let mut field = SeaOfFishes::from_input(data.get(0).unwrap());
-    field.tick_n_days(256);
-    return field.count_the_pool();
-}
\ No newline at end of file
+impl Solution for Day6 {
+    type Input = SeaOfFishes;
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Lanternfish"
+    }
+
+    /// the whole sea is given in the first line
+    fn parse(&self, lines: &[String]) -> Result<SeaOfFishes, ParseError> {
+        SeaOfFishes::from_input(lines.first().ok_or(ParseError::end_of_input(0, "<days>,<days>,..."))?)
+    }
+
+    fn part1(&self, sea: &SeaOfFishes) -> Result<Answer, SolveError> {
+        let mut field = sea.clone();
+        field.tick_n_days(80);
+        Ok(field.count_the_pool().into())
+    }
+
+    fn part2(&self, sea: &SeaOfFishes) -> Result<Answer, SolveError> {
+        let mut field = sea.clone();
+        field.tick_n_days(256);
+        Ok(field.count_the_pool().into())
+    }
+}
@@ -1,29 +1,30 @@
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
 use std::collections::HashMap;
-use crate::day4::parse_line_of_numbers;
+use crate::log::{debug, info};
+use crate::parse_error::ParseError;
+use crate::parser::{self, integer, list};
+use crate::solution::{Answer, Solution, SolveError};
 
 #[derive(Debug)]
-struct Field {
+pub struct Field {
     // map from position -> number crabs at the position
     positions: HashMap<u64, u64>
 }
 
 impl Field {
-    fn from_line(line: &String) -> Self {
-        let values =  parse_line_of_numbers::<u64>(line, ',');
+    fn from_line(line: &str) -> Result<Self, ParseError> {
         let mut vec : HashMap<u64, u64> = HashMap::new();
 
         // Just count how often each number appears
-        for number in values {
-            if !vec.contains_key(&number) {
-                vec.insert(number, 0);
-            }
-            let to_change = vec.get_mut(&number).unwrap();
-            *to_change += 1;
+        for number in parser::line(0, line, list(",", integer::<u64>("<position>")))? {
+            *vec.entry(number).or_insert(0) += 1;
         }
 
-        return Field {
-            positions: vec.into()
-        }
+        return Ok(Field {
+            positions: vec
+        });
     }
 
     /// Calculates the cost if all crabs move to [position]
@@ -33,7 +34,7 @@ impl Field {
         // Iterate over all crab positions (key is the position)
         for key in self.positions.keys() {
             // distance from the crab position to the target position
-            let dist = (*key as i64 - position as i64).abs() as u64;
+            let dist = (*key as i64 - position as i64).unsigned_abs();
 
             if linear_cost { // task 1
                 // one position movement costs exactly one fuel (linear cost)
@@ -68,18 +69,35 @@ impl Field {
     }
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 7 task 1");
-    let field = Field::from_line(data.get(0).unwrap());
-    let least_fuel = field.calculate_minimum(true);
-    println!("{:?}: least fuel {}", field, least_fuel);
-    return least_fuel;
-}
+pub struct Day7;
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 7 task 2");
-    let field = Field::from_line(data.get(0).unwrap());
-    let least_fuel = field.calculate_minimum(false);
-    println!("{:?}: least fuel {}", field, least_fuel);
-    return least_fuel;
-}
\ No newline at end of file
+impl Solution for Day7 {
+    type Input = Field;
+
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "The Treachery of Whales"
+    }
+
+    /// all crab positions are given in the first line
+    fn parse(&self, lines: &[String]) -> Result<Field, ParseError> {
+        Field::from_line(lines.first().ok_or(ParseError::end_of_input(0, "<position>,<position>,..."))?)
+    }
+
+    fn part1(&self, field: &Field) -> Result<Answer, SolveError> {
+        let least_fuel = field.calculate_minimum(true);
+        debug!("{:?}", field);
+        info!("least fuel {}", least_fuel);
+        Ok(least_fuel.into())
+    }
+
+    fn part2(&self, field: &Field) -> Result<Answer, SolveError> {
+        let least_fuel = field.calculate_minimum(false);
+        debug!("{:?}", field);
+        info!("least fuel {}", least_fuel);
+        Ok(least_fuel.into())
+    }
+}
@@ -1,4 +1,11 @@
+// the original solutions spell out their `return`s
+#![allow(clippy::needless_return)]
+
 use std::collections::{HashSet};
+use crate::log::{debug};
+use crate::parse_error::ParseError;
+use crate::parser::{self, chars, exactly, list, map, pair, verify, Parser, Section};
+use crate::solution::{Answer, Solution, SolveError};
 
 /// Used Layout
 ///  1111
@@ -21,7 +28,7 @@ impl SegmentClock {
 
     /// Parse an input sequence line as in `adc` (meaning having those segments turned on)
     /// to its corresponding digit
-    pub fn to_digit(&self, line: &String) -> Result<u8, ()>{
+    pub fn to_digit(&self, line: &str) -> Result<u8, ()>{
         // Iterate over all patterns
         for (res, expected_segments) in [
             vec!(0, 1, 2, 4, 5, 6), // 0
@@ -87,7 +94,7 @@ impl SegmentClock {
         let seven_chars: HashSet<char> = string_to_char_set(&seven);
 
         // ...this lets us deduce the first segment directly by diffing the 7 with the 1 (there is only one segment left)
-        let segment_1 = *seven_chars.difference(&one_char).collect::<Vec<&char>>().first().unwrap().clone();
+        let segment_1 = **seven_chars.difference(&one_char).collect::<Vec<&char>>().first().unwrap();
 
         // by intersecting (set AND) we overlap the digits one 3 and 5 which leaves os with segments 1,3,5
         let segments_1_3_5 = two_and_five_element_0.intersection(&two_and_five_element_1).cloned().collect::<HashSet<char>>().intersection(&two_and_five_element_2).cloned().collect();
@@ -96,45 +103,43 @@ impl SegmentClock {
         let segments_3_5 = sub_char_from_hashset(&segments_1_3_5, segment_1);
 
         // .. and by removing the segments of the digit 4 we we have segment 5 left
-        let segment_5 = *segments_3_5.difference(&string_to_char_set(&four)).collect::<Vec<&char>>().first().unwrap().clone();
+        let segment_5 = **segments_3_5.difference(&string_to_char_set(&four)).collect::<Vec<&char>>().first().unwrap();
         // and we use the known segment 5 we have segment 3 left
-        let segment_3 = *sub_char_from_hashset(&segments_3_5, segment_5).iter().collect::<Vec<&char>>().first().unwrap().clone();
+        let segment_3 = **sub_char_from_hashset(&segments_3_5, segment_5).iter().collect::<Vec<&char>>().first().unwrap();
 
         // ... and so on ;)
         let four_minus_one: HashSet<char> = string_to_char_set(&four)
-            .difference(&string_to_char_set(&one))
-            .map(|c| c.clone()).collect();
-        let segment_0 = *sub_char_from_hashset(&four_minus_one.clone(), segment_3).iter().collect::<Vec<&char>>().first().unwrap().clone();
+            .difference(&string_to_char_set(&one)).copied().collect();
+        let segment_0 = **sub_char_from_hashset(&four_minus_one.clone(), segment_3).iter().collect::<Vec<&char>>().first().unwrap();
         let eight_minus_four: HashSet<char> = string_to_char_set(&eight)
-            .difference(&string_to_char_set(&four))
-            .map(|c|c.clone())
+            .difference(&string_to_char_set(&four)).copied()
             .collect();
 
         let eight_minus_four_minus_segment_1_minus_segment_5 = sub_char_from_hashset(
             &sub_char_from_hashset(&eight_minus_four, segment_1),
             segment_5);
 
-        let segment_4 = *eight_minus_four_minus_segment_1_minus_segment_5.iter().collect::<Vec<&char>>().first().unwrap().clone();
+        let segment_4 = **eight_minus_four_minus_segment_1_minus_segment_5.iter().collect::<Vec<&char>>().first().unwrap();
 
         // Deduce 5
         let mut segment_6= 'x';
         for el in two_and_five_and_three {
             if el.contains(segment_0) {
                 // we know that this must be the five
-                segment_6 = *string_to_char_set(el)
+                segment_6 = **string_to_char_set(el)
                     .difference(&HashSet::<char>::from_iter([
                         segment_0,
                         segment_1,
                         segment_3,
-                        segment_5])).map(|c| c.clone())
+                        segment_5])).copied()
                     .collect::<HashSet<char>>()
                     .iter().collect::<Vec<&char>>()
-                    .first().unwrap().clone();
+                    .first().unwrap();
             }
         }
 
         // deduce section 2 (c)
-        let segment_2 = *sub_char_from_hashset(&string_to_char_set(&one), segment_6).iter().collect::<Vec<&char>>().first().unwrap().clone();
+        let segment_2 = **sub_char_from_hashset(&string_to_char_set(&one), segment_6).iter().collect::<Vec<&char>>().first().unwrap();
         self.segments_to_letters[1] = Some(segment_1);
         self.segments_to_letters[5] = Some(segment_5);
         self.segments_to_letters[3] = Some(segment_3);
@@ -148,8 +153,8 @@ impl SegmentClock {
 }
 
 /// Represents the input lines
-#[derive(Debug)]
-struct SegmentLine {
+#[derive(Debug, Clone)]
+pub struct SegmentLine {
     /// Part before the |-delimiter
     input_part: Vec<String>,
     /// part after
@@ -157,15 +162,9 @@ struct SegmentLine {
 }
 
 impl SegmentLine {
-    pub fn from_input_line(line: &String) -> Self {
-        let parts: Vec<&str> = line.split(" | ").collect();
-        let first_part = parts.get(0).unwrap();
-        let second_part = parts.get(1).unwrap();
-
-        return Self {
-            input_part: first_part.split(' ').map(|i| String::from(i)).collect(),
-            output_part: second_part.split(' ').map(|i| String::from(i)).collect()
-        }
+    /// parses a line (at index `index`) like `<10 patterns> | <4 digits>`
+    pub fn from_input_line(index: usize, line: &str) -> Result<Self, ParseError> {
+        parser::line(index, line, segment_line())
     }
 
 
@@ -186,51 +185,105 @@ impl SegmentLine {
     }
 }
 
+/// `<10 patterns> | <4 digits>`
+fn segment_line<'a>() -> impl Parser<'a, SegmentLine> {
+    map(
+        pair(" | ", "<10 patterns> | <4 digits>", patterns(10, "10 patterns"), patterns(4, "4 patterns")),
+        |(input_part, output_part)| SegmentLine { input_part, output_part },
+    )
+}
+
+/// Exactly `count` space separated patterns made of (at most 7 of) the segments `a`-`g`
+fn patterns<'a>(count: usize, expected: &'static str) -> impl Parser<'a, Vec<String>> {
+    let segments = chars("<segments a-g>", |c| ('a'..='g').contains(&c).then_some(c));
+    let pattern = verify(map(segments, String::from_iter), "<segments a-g>", |pattern| pattern.len() <= 7);
+    exactly(count, expected, list(" ", pattern))
+}
+
 /// Helper function to remove a single char from a HashMap
 pub fn sub_char_from_hashset(set: &HashSet<char>, c: char) -> HashSet<char> {
     let other = HashSet::<_>::from_iter([c]);
-    set.difference(&other).map(|d| d.clone()).collect()
+    set.difference(&other).copied().collect()
 }
 
 /// Helper function to turn each char into a HashSet Entry
-pub fn string_to_char_set(line: &String) -> HashSet<char> {
+pub fn string_to_char_set(line: &str) -> HashSet<char> {
     line.chars().collect()
 }
 
 /// Filters a list of inputs by length
-pub fn filter_by_length(input: &Vec<String>, desired_length: u64) -> Vec<&String> {
+pub fn filter_by_length(input: &[String], desired_length: u64) -> Vec<&String> {
     input.iter().filter(|line| line.len() == desired_length as usize).collect()
 }
 
 /// returns a [SegmentLine] for each input String
-fn to_segment_lines(data: &Vec<String>) -> Vec<SegmentLine> {
-    return data.iter().map(|line| SegmentLine::from_input_line(line)).collect();
+fn to_segment_lines(data: &[String]) -> Result<Vec<SegmentLine>, ParseError> {
+    Section::whole(data).each_line(segment_line())
 }
 
-pub fn task1(data: &Vec<String>) -> u64 {
-    println!("Executing day 8 task 2");
-    let segment_lines = to_segment_lines(data);
-    let mut sum_of_requested_numbers = 0 as u64;
-    for line in segment_lines.iter() {
-        sum_of_requested_numbers +=
-            line.count_output_digit_1() +
-            line.count_output_digit_4() +
-            line.count_output_digit_7() +
-            line.count_output_digit_8();
-    }
-    println!("Segments: {:?}", segment_lines);
-    return sum_of_requested_numbers;
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<SegmentLine>;
+
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Seven Segment Search"
+    }
+
+    fn parse(&self, lines: &[String]) -> Result<Vec<SegmentLine>, ParseError> {
+        to_segment_lines(lines)
+    }
+
+    fn part1(&self, segment_lines: &Vec<SegmentLine>) -> Result<Answer, SolveError> {
+        let mut sum_of_requested_numbers = 0_u64;
+        for line in segment_lines.iter() {
+            sum_of_requested_numbers +=
+                line.count_output_digit_1() +
+                line.count_output_digit_4() +
+                line.count_output_digit_7() +
+                line.count_output_digit_8();
+        }
+        debug!("Segments: {:?}", segment_lines);
+        Ok(sum_of_requested_numbers.into())
+    }
+
+    fn part2(&self, segment_lines: &Vec<SegmentLine>) -> Result<Answer, SolveError> {
+        let mut sum: u64 = 0;
+        for line in segment_lines {
+            let mut clock = SegmentClock::from_segment_line(line.clone());
+            clock.deduce_segments();
+            sum += clock.output_to_number();
+            debug!("{:?}", clock);
+        }
+        Ok(sum.into())
+    }
 }
 
-pub fn task2(data: &Vec<String>) -> u64 {
-    println!("Executing day 8 task 2");
-    let segment_lines = to_segment_lines(data);
-    let mut sum: u64 = 0;
-    for line in segment_lines {
-        let mut clock = SegmentClock::from_segment_line(line);
-        clock.deduce_segments();
-        sum += clock.output_to_number();
-        println!("{:?}", clock);
-    }
-    return sum;
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_a_char_from_a_set() {
+        let set = string_to_char_set(&String::from("abc"));
+        assert_eq!(sub_char_from_hashset(&set, 'b'), HashSet::from(['a', 'c']));
+        // the original stays untouched
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn removing_a_missing_char_changes_nothing() {
+        let set = string_to_char_set(&String::from("abc"));
+        assert_eq!(sub_char_from_hashset(&set, 'g'), set);
+    }
+
+    #[test]
+    fn rejects_lines_without_separator() {
+        let lines = vec![String::from("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab")];
+        assert!(Day8.parse(&lines).is_err());
+    }
+}
@@ -0,0 +1,435 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::expected::{Dataset, ExpectedAnswers};
+use crate::log::{error, info};
+use crate::registry;
+use crate::runner::{self, InputKind};
+use crate::solution::{Answer, Part};
+
+/// A block of puzzle text (a `<pre>` on the website) together with the sentence introducing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// the line ending with `:` right before the block
+    pub intro: String,
+    pub lines: Vec<String>,
+}
+
+/// The numbers stated in the description of one part (without the question and the puzzle answer)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stated {
+    pub part: Part,
+    pub numbers: Vec<u64>,
+}
+
+impl Stated {
+    /// The example answer is (almost always) the last number before the question
+    pub fn guess(&self) -> Option<u64> {
+        self.numbers.last().copied()
+    }
+}
+
+/// What can be read out of a `task.txt`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskExamples {
+    pub blocks: Vec<Block>,
+    /// one entry per part the description contains
+    pub stated: Vec<Stated>,
+}
+
+/// Prose is a sentence (or a few) on a single line. Example data rarely contains three spaces
+/// and ends with a punctuation mark
+fn is_prose(line: &str) -> bool {
+    let line = line.trim();
+    line.ends_with(['.', '!', '?', ':', ')']) && line.matches(' ').count() >= 3
+}
+
+/// A line ending with `:` which introduces a block, like `For example:`
+fn is_intro(line: &str) -> bool {
+    let line = line.trim();
+    line.ends_with(':') && (is_prose(line) || line.to_lowercase().contains("example"))
+}
+
+/// All (unsigned) numbers in `text`, `26984457539` as well as the `1` and `2` of `1,2`
+fn numbers(text: &str) -> Vec<u64> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|number| number.parse::<u64>().ok())
+        .collect()
+}
+
+impl TaskExamples {
+    /// Finds the blocks and stated numbers of a puzzle description (as written by `fetch`)
+    pub fn parse(task: &str) -> Self {
+        let lines: Vec<&str> = task.lines().collect();
+
+        let mut blocks = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let intro = lines[i].trim();
+            i += 1;
+            if !is_intro(intro) {
+                continue;
+            }
+
+            let start = i;
+            while i < lines.len() && !is_prose(lines[i]) && !is_intro(lines[i]) && !lines[i].starts_with("--- ") {
+                i += 1;
+            }
+            let mut block: Vec<String> = lines[start..i].iter().map(|line| String::from(line.trim_end())).collect();
+            while block.last().is_some_and(|line| line.is_empty()) {
+                block.pop();
+            }
+            let first = block.iter().position(|line| !line.is_empty()).unwrap_or(block.len());
+            if first < block.len() {
+                blocks.push(Block { intro: String::from(intro), lines: block.split_off(first) });
+            }
+        }
+
+        let part_two = lines.iter().position(|line| line.starts_with("--- Part Two ---"));
+        let sections = match part_two {
+            Some(pos) => vec![(Part::One, &lines[..pos]), (Part::Two, &lines[pos + 1..])],
+            None => vec![(Part::One, &lines[..])],
+        };
+        let stated = sections
+            .into_iter()
+            .map(|(part, section)| {
+                let mut prose: Vec<&str> = section
+                    .iter()
+                    .take_while(|line| !line.starts_with("Your puzzle answer was"))
+                    .filter(|line| is_prose(line))
+                    .copied()
+                    .collect();
+                // the last sentence asks the question, its numbers belong to the real puzzle
+                prose.pop();
+                Stated { part, numbers: prose.iter().flat_map(|line| numbers(line)).collect() }
+            })
+            .collect();
+
+        Self { blocks, stated }
+    }
+
+    /// The first block introduced as an example, that's what goes into `testinput.txt`
+    pub fn example(&self) -> Option<&Block> {
+        self.blocks.iter().find(|block| block.intro.to_lowercase().contains("example"))
+    }
+
+    pub fn stated(&self, part: Part) -> Option<&Stated> {
+        self.stated.iter().find(|stated| stated.part == part)
+    }
+}
+
+/// How `testinput.txt` compares to the description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputCheck {
+    /// it is one of the blocks of the description
+    Matches,
+    /// it is a block of the description with different line breaks (the website wraps long lines)
+    Reflowed,
+    /// it differs from the example; `line` (1-based) is the first line which does
+    Drift { line: usize, expected: Option<String>, actual: Option<String> },
+    /// there is no `testinput.txt` (or it is empty)
+    Missing,
+    /// the description contains no example (or there is no description)
+    NoExample,
+}
+
+impl InputCheck {
+    pub fn is_drift(&self) -> bool {
+        matches!(self, Self::Drift { .. })
+    }
+}
+
+impl fmt::Display for InputCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |line: &Option<String>| match line {
+            Some(line) => format!("`{}`", line),
+            None => String::from("nothing"),
+        };
+        match self {
+            Self::Matches => write!(f, "matches"),
+            Self::Reflowed => write!(f, "matches (reflowed)"),
+            Self::Drift { line, expected, actual } =>
+                write!(f, "DRIFT at line {}: expected {}, found {}", line, show(expected), show(actual)),
+            Self::Missing => write!(f, "missing"),
+            Self::NoExample => write!(f, "no example found"),
+        }
+    }
+}
+
+/// Compares the lines of `testinput.txt` to the blocks of the description
+pub fn check_input(task: &TaskExamples, test_input: &[String]) -> InputCheck {
+    let example = match task.example() {
+        Some(example) => example,
+        None => return InputCheck::NoExample,
+    };
+    if test_input.iter().all(|line| line.trim().is_empty()) {
+        return InputCheck::Missing;
+    }
+
+    let trimmed = |lines: &[String]| lines.iter().map(|line| String::from(line.trim_end())).collect::<Vec<String>>();
+    let tokens = |lines: &[String]| lines.iter().flat_map(|line| line.split_whitespace()).map(String::from).collect::<Vec<String>>();
+
+    let mut test_input = trimmed(test_input);
+    while test_input.last().is_some_and(|line| line.is_empty()) {
+        test_input.pop();
+    }
+    if task.blocks.iter().any(|block| block.lines == test_input) {
+        return InputCheck::Matches;
+    }
+    if task.blocks.iter().any(|block| tokens(&block.lines) == tokens(&test_input)) {
+        return InputCheck::Reflowed;
+    }
+
+    let line = (0..)
+        .find(|i| example.lines.get(*i) != test_input.get(*i))
+        .unwrap();
+    InputCheck::Drift { line: line + 1, expected: example.lines.get(line).cloned(), actual: test_input.get(line).cloned() }
+}
+
+/// How the recorded test answer of a part compares to the description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerCheck {
+    /// the recorded answer is stated in the description
+    Stated(u64),
+    /// the description doesn't mention the recorded answer
+    Drift { recorded: u64, guess: Option<u64> },
+    /// nothing recorded yet
+    Unrecorded { guess: Option<u64> },
+    /// drawings and text can't be looked for
+    Unchecked,
+}
+
+impl AnswerCheck {
+    pub fn is_drift(&self) -> bool {
+        matches!(self, Self::Drift { .. })
+    }
+}
+
+impl fmt::Display for AnswerCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guess = |guess: &Option<u64>| match guess {
+            Some(guess) => format!("probably {}", guess),
+            None => String::from("no guess"),
+        };
+        match self {
+            Self::Stated(answer) => write!(f, "{} stated", answer),
+            Self::Drift { recorded, guess: g } => write!(f, "DRIFT: {} not stated, {}", recorded, guess(g)),
+            Self::Unrecorded { guess: g } => write!(f, "unrecorded, {}", guess(g)),
+            Self::Unchecked => write!(f, "-"),
+        }
+    }
+}
+
+/// Looks for the recorded test answer in what the description of `part` states
+pub fn check_answer(task: &TaskExamples, part: Part, recorded: Option<&Answer>) -> AnswerCheck {
+    let stated = match task.stated(part) {
+        Some(stated) => stated,
+        None => return AnswerCheck::Unchecked,
+    };
+
+    match recorded {
+        Some(Answer::Integer(answer)) if stated.numbers.contains(answer) => AnswerCheck::Stated(*answer),
+        Some(Answer::Integer(answer)) => AnswerCheck::Drift { recorded: *answer, guess: stated.guess() },
+        Some(_) => AnswerCheck::Unchecked,
+        None => AnswerCheck::Unrecorded { guess: stated.guess() },
+    }
+}
+
+/// The examples of one day compared to what is in the repository
+#[derive(Debug)]
+pub struct ExampleReport {
+    pub day: u8,
+    pub input: InputCheck,
+    pub answers: Vec<(Part, AnswerCheck)>,
+    /// files written because of `write`
+    pub written: Vec<PathBuf>,
+}
+
+impl ExampleReport {
+    pub fn has_drift(&self) -> bool {
+        self.input.is_drift() || self.answers.iter().any(|(_, check)| check.is_drift())
+    }
+}
+
+fn day_file(day: u8, name: &str) -> PathBuf {
+    PathBuf::from(format!("./src/day{}/{}", day, name))
+}
+
+/// Checks `testinput.txt` and the recorded test answers of `day` against its `task.txt`.
+/// With `write`, a missing `testinput.txt` is filled with the example and unrecorded answers are
+/// recorded if the solution computes one of the stated numbers (nothing that exists is overwritten)
+pub fn check_day(day: u8, write: bool) -> Result<ExampleReport, String> {
+    let task_path = day_file(day, "task.txt");
+    let task = fs::read_to_string(&task_path).map_err(|err| format!("Couldn't read {}: {}", task_path.display(), err))?;
+    let task = TaskExamples::parse(&task);
+
+    let input_path = day_file(day, "testinput.txt");
+    let test_input: Vec<String> = fs::read_to_string(&input_path)
+        .map(|content| content.lines().map(String::from).collect())
+        .unwrap_or_default();
+    let mut answers = ExpectedAnswers::load(day)?;
+
+    let mut report = ExampleReport {
+        day,
+        input: check_input(&task, &test_input),
+        answers: Part::BOTH
+            .iter()
+            .map(|part| (*part, check_answer(&task, *part, answers.get(Dataset::Test, *part))))
+            .collect(),
+        written: Vec::new(),
+    };
+    if !write {
+        return Ok(report);
+    }
+
+    if let (InputCheck::Missing, Some(example)) = (&report.input, task.example()) {
+        fs::write(&input_path, example.lines.join("\n") + "\n")
+            .map_err(|err| format!("Couldn't write {}: {}", input_path.display(), err))?;
+        info!("Day {}: wrote the example below `{}`", day, example.intro);
+        report.written.push(input_path);
+        report.input = InputCheck::Matches;
+    }
+
+    // a guess is only recorded if the solution agrees (the guess is wrong for e.g. day 12)
+    let solution = registry::find(day);
+    let data = InputKind::Test.load(day, true).ok();
+    let mut recorded = false;
+    for (part, check) in report.answers.iter_mut() {
+        let (solution, data) = match (&*check, solution, &data) {
+            (AnswerCheck::Unrecorded { .. }, Some(solution), Some(data)) => (solution, data),
+            _ => continue,
+        };
+        let stated = task.stated(*part).map(|stated| stated.numbers.as_slice()).unwrap_or_default();
+        match runner::run_part(solution, *part, &InputKind::Test, data).outcome {
+            Ok(Answer::Integer(answer)) if stated.contains(&answer) => {
+                answers.insert(Dataset::Test, *part, Answer::Integer(answer));
+                *check = AnswerCheck::Stated(answer);
+                recorded = true;
+            },
+            Ok(answer) => info!("Day {} part {}: the solution's {} is not stated, not recording it", day, part, answer),
+            Err(msg) => info!("Day {} part {}: {}", day, part, msg),
+        }
+    }
+    if recorded {
+        answers.save(day).map_err(|err| format!("Couldn't record answers of day {}: {}", day, err))?;
+        report.written.push(ExpectedAnswers::path_for(day));
+    }
+
+    Ok(report)
+}
+
+/// Checks every day in `days`; days which couldn't be checked are logged and left out
+pub fn check_days(days: &[u8], write: bool) -> (Vec<ExampleReport>, bool) {
+    let mut failed = false;
+    let reports = days
+        .iter()
+        .filter_map(|day| match check_day(*day, write) {
+            Ok(report) => Some(report),
+            Err(msg) => {
+                error!("Day {}: {}", day, msg);
+                failed = true;
+                None
+            }
+        })
+        .collect();
+    (reports, failed)
+}
+
+/// Prints one line per day and what was written
+pub fn print_report(reports: &[ExampleReport]) {
+    println!("{:>4} | {:<30} | {:<30} | Part 2", "Day", "testinput.txt", "Part 1");
+    println!("{}", "-".repeat(100));
+    for report in reports {
+        let answer = |part: Part| report.answers
+            .iter()
+            .find(|(p, _)| *p == part)
+            .map(|(_, check)| check.to_string())
+            .unwrap_or_default();
+        println!("{:>4} | {:<30} | {:<30} | {}", report.day, report.input.to_string(), answer(Part::One), answer(Part::Two));
+    }
+
+    for path in reports.iter().flat_map(|report| &report.written) {
+        println!("wrote {}", path.display());
+    }
+    println!("\n{} of {} days drifted from their description",
+             reports.iter().filter(|report| report.has_drift()).count(),
+             reports.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK: &str = "\
+--- Day 1: Sonar Sweep ---
+
+As the submarine drops below the surface, it measures the depth. For example, suppose you had the following report:
+
+199
+200
+
+208
+
+This report indicates that, scanning outward from the submarine, the depths are 199 and 200.
+
+In this example, there are 2 measurements that are larger than the previous measurement.
+
+How many measurements are larger than the previous measurement?
+
+Your puzzle answer was 1527.
+
+--- Part Two ---
+
+In the above example, the windows sum up to 1 and 5 (and so on):
+
+A: 607
+
+Again, there are 5 sums that are larger than the previous sum.
+
+Consider sums of a three-measurement sliding window. How many sums are larger than 3 previous sums?
+";
+
+    fn lines(input: &[&str]) -> Vec<String> {
+        input.iter().map(|line| String::from(*line)).collect()
+    }
+
+    #[test]
+    fn finds_blocks_and_stated_numbers() {
+        let task = TaskExamples::parse(TASK);
+        assert_eq!(task.blocks.len(), 2);
+        assert_eq!(task.example().unwrap().lines, lines(&["199", "200", "", "208"]));
+        assert_eq!(task.blocks[1].lines, lines(&["A: 607"]));
+
+        let one = task.stated(Part::One).unwrap();
+        assert_eq!(one.numbers, vec![199, 200, 2]);
+        assert_eq!(task.stated(Part::Two).unwrap().guess(), Some(5));
+    }
+
+    #[test]
+    fn compares_the_test_input() {
+        let task = TaskExamples::parse(TASK);
+        assert_eq!(check_input(&task, &lines(&["199", "200 ", "", "208", ""])), InputCheck::Matches);
+        assert_eq!(check_input(&task, &lines(&["199 200", "", "208"])), InputCheck::Reflowed);
+        assert_eq!(check_input(&task, &lines(&["199", "201"])), InputCheck::Drift {
+            line: 2,
+            expected: Some(String::from("200")),
+            actual: Some(String::from("201")),
+        });
+        assert_eq!(check_input(&task, &lines(&["", " "])), InputCheck::Missing);
+        assert_eq!(check_input(&TaskExamples::parse("Nothing: here"), &lines(&["1"])), InputCheck::NoExample);
+    }
+
+    #[test]
+    fn looks_for_recorded_answers() {
+        let task = TaskExamples::parse(TASK);
+        assert_eq!(check_answer(&task, Part::One, Some(&Answer::Integer(2))), AnswerCheck::Stated(2));
+        // 1527 is the puzzle answer, 3 is from the question
+        assert_eq!(check_answer(&task, Part::One, Some(&Answer::Integer(1527))),
+                   AnswerCheck::Drift { recorded: 1527, guess: Some(2) });
+        assert_eq!(check_answer(&task, Part::Two, Some(&Answer::Integer(3))),
+                   AnswerCheck::Drift { recorded: 3, guess: Some(5) });
+        assert_eq!(check_answer(&task, Part::Two, None), AnswerCheck::Unrecorded { guess: Some(5) });
+        assert_eq!(check_answer(&task, Part::Two, Some(&Answer::Text(String::from("AB")))), AnswerCheck::Unchecked);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::parse_error::ParseError;
+use crate::runner::InputKind;
+use crate::solution::{Answer, Part};
+
+/// Which of the two bundled inputs an expected answer belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dataset {
+    Test,
+    Real,
+}
+
+impl Dataset {
+    pub const BOTH: [Dataset; 2] = [Dataset::Test, Dataset::Real];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Test => "test",
+            Self::Real => "real",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "test" => Some(Self::Test),
+            "real" => Some(Self::Real),
+            _ => None,
+        }
+    }
+
+    /// Only the bundled inputs have recorded answers
+    pub fn from_input(input: &InputKind) -> Option<Self> {
+        match input {
+            InputKind::Test => Some(Self::Test),
+            InputKind::Real => Some(Self::Real),
+            _ => None,
+        }
+    }
+
+    pub fn input_kind(&self) -> InputKind {
+        match self {
+            Self::Test => InputKind::Test,
+            Self::Real => InputKind::Real,
+        }
+    }
+}
+
+/// The recorded answers of a single day, stored in `src/dayXY/answers.txt`.
+///
+/// Every entry is one line `<test|real> <1|2> = <answer>`.
+/// Multi-line answers (ASCII art) leave the value empty and
+/// list their rows on the following lines, each prefixed with `|`:
+/// ```text
+/// real 2 =
+/// |#  #
+/// |####
+/// ```
+/// Lines starting with `#` are comments
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExpectedAnswers {
+    answers: BTreeMap<(Dataset, Part), Answer>,
+}
+
+impl ExpectedAnswers {
+    /// Where the answers of `day` live
+    pub fn path_for(day: u8) -> PathBuf {
+        PathBuf::from(format!("./src/day{}/answers.txt", day))
+    }
+
+    /// Loads the answers of `day`. A missing file just means nothing was recorded yet
+    pub fn load(day: u8) -> Result<Self, String> {
+        let path = Self::path_for(day);
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|err| format!("{}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Couldn't read {}: {}", path.display(), err)),
+        }
+    }
+
+    /// Parses the content of an answers file (see [ExpectedAnswers])
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut answers = BTreeMap::new();
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            if line.trim().is_empty() || line.starts_with('#') {
+                i += 1;
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ParseError::at_line(i, line, "<test|real> <1|2> = <answer>"))?;
+
+            let mut key_parts = key.split_whitespace();
+            let dataset_str = key_parts.next().unwrap_or(key);
+            let dataset = Dataset::from_name(dataset_str)
+                .ok_or(ParseError::at_part(i, line, dataset_str, "test|real"))?;
+            let part_str = key_parts.next().unwrap_or(key);
+            let part = part_str
+                .parse::<u8>()
+                .ok()
+                .and_then(Part::from_number)
+                .ok_or(ParseError::at_part(i, line, part_str, "1|2"))?;
+
+            let value = value.trim();
+            let answer = if value.is_empty() {
+                // ASCII art: collect all following `|` lines
+                let mut rows = Vec::new();
+                while let Some(row) = lines.get(i + 1).and_then(|l| l.strip_prefix('|')) {
+                    rows.push(String::from(row));
+                    i += 1;
+                }
+                if rows.is_empty() {
+                    return Err(ParseError::at_line(i, line, "<answer> or rows starting with |"));
+                }
+                Answer::Art(rows)
+            } else if let Ok(number) = value.parse::<u64>() {
+                Answer::Integer(number)
+            } else {
+                Answer::Text(String::from(value))
+            };
+
+            answers.insert((dataset, part), answer);
+            i += 1;
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, dataset: Dataset, part: Part) -> Option<&Answer> {
+        self.answers.get(&(dataset, part))
+    }
+
+    pub fn insert(&mut self, dataset: Dataset, part: Part, answer: Answer) {
+        self.answers.insert((dataset, part), answer);
+    }
+
+    /// Turns the answers back into the file format
+    pub fn to_file_content(&self) -> String {
+        let mut content = String::from("# <test|real> <part> = <expected answer>\n");
+        for ((dataset, part), answer) in &self.answers {
+            match answer {
+                Answer::Art(rows) => {
+                    content += &format!("{} {} =\n", dataset.name(), part);
+                    for row in rows {
+                        content += &format!("|{}\n", row.trim_end());
+                    }
+                },
+                other => content += &format!("{} {} = {}\n", dataset.name(), part, other),
+            }
+        }
+        content
+    }
+
+    pub fn save(&self, day: u8) -> io::Result<()> {
+        fs::write(Self::path_for(day), self.to_file_content())
+    }
+}
+
+/// Compares two answers; trailing whitespace of ASCII art rows doesn't matter
+pub fn answers_match(expected: &Answer, actual: &Answer) -> bool {
+    match (expected, actual) {
+        (Answer::Art(expected), Answer::Art(actual)) => {
+            let trim = |rows: &Vec<String>| rows
+                .iter()
+                .map(|row| String::from(row.trim_end()))
+                .collect::<Vec<String>>();
+            trim(expected) == trim(actual)
+        },
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_file_format() {
+        let mut answers = ExpectedAnswers::default();
+        answers.insert(Dataset::Test, Part::One, Answer::Integer(17));
+        answers.insert(Dataset::Real, Part::Two, Answer::Art(vec![String::from("# #"), String::from(" #")]));
+
+        let parsed = ExpectedAnswers::parse(&answers.to_file_content()).unwrap();
+        assert_eq!(parsed, answers);
+    }
+
+    #[test]
+    fn reports_bad_keys() {
+        let err = ExpectedAnswers::parse("# comment\ntest 3 = 5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 6));
+    }
+
+    #[test]
+    fn art_ignores_trailing_whitespace() {
+        let expected = Answer::Art(vec![String::from("#"), String::from("##")]);
+        let actual = Answer::Art(vec![String::from("#  "), String::from("##")]);
+        assert!(answers_match(&expected, &actual));
+        assert!(!answers_match(&Answer::Integer(1), &Answer::Integer(2)));
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::html;
+use crate::http;
+use crate::log::{debug, info};
+
+/// The file (below the crate root) remembering when the last request was sent
+pub const STAMP_FILE: &str = ".aoc_last_request";
+
+/// What can be downloaded for a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    /// the puzzle description, stored as text in `task.txt`
+    Task,
+    /// the personal puzzle input, stored in `input.txt`
+    Input,
+}
+
+impl Resource {
+    pub const BOTH: [Resource; 2] = [Resource::Task, Resource::Input];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Task => "task.txt",
+            Self::Input => "input.txt",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "task" => Some(Self::Task),
+            "input" => Some(Self::Input),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Task => write!(f, "task"),
+            Self::Input => write!(f, "input"),
+        }
+    }
+}
+
+/// Everything that can go wrong while fetching
+#[derive(Debug)]
+pub enum FetchError {
+    /// personal inputs need the session cookie
+    NoSession,
+    /// the server answered, but not with the content
+    Status { url: String, status: u16 },
+    /// the server couldn't be reached (or curl failed)
+    Transport(String),
+    /// the downloaded content couldn't be stored
+    Write(PathBuf, io::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSession => write!(f, "No session token configured (set `session = ...` in the config file)"),
+            Self::Status { url, status } => write!(f, "{} answered with status {}", url, status),
+            Self::Transport(msg) => write!(f, "{}", msg),
+            Self::Write(path, err) => write!(f, "Couldn't write {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// What happened to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// it was there already, nothing was requested
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+impl Fetched {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Cached(path) | Self::Downloaded(path) => path,
+        }
+    }
+}
+
+/// Makes sure there are at least `min_interval` between two requests, even across runs of `aoc`
+/// (the time of the last request is kept in a stamp file)
+#[derive(Debug)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    stamp: Option<PathBuf>,
+    last: Option<SystemTime>,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration, stamp: Option<PathBuf>) -> Self {
+        let last = stamp
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|millis| millis.trim().parse::<u64>().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        Self { min_interval, stamp, last }
+    }
+
+    /// How long we still have to wait before the next request may be sent
+    pub fn remaining(&self) -> Duration {
+        match self.last {
+            // if the clock went backwards `elapsed` fails, then we wait the whole interval
+            Some(last) => self.min_interval.saturating_sub(last.elapsed().unwrap_or_default()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Sleeps until the next request may be sent and remembers that it is sent now
+    pub fn wait(&mut self) {
+        let remaining = self.remaining();
+        if !remaining.is_zero() {
+            info!("Waiting {:?} before the next request", remaining);
+            thread::sleep(remaining);
+        }
+
+        let now = SystemTime::now();
+        self.last = Some(now);
+        if let Some(stamp) = &self.stamp {
+            let millis = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+            // not being able to remember it only weakens the limit for the next run
+            let _ = fs::write(stamp, millis.to_string());
+        }
+    }
+}
+
+/// Downloads puzzle descriptions and inputs into `src/day{N}/` below `root`.
+/// Files which already have content are never requested again
+#[derive(Debug)]
+pub struct Fetcher {
+    config: Config,
+    root: PathBuf,
+    limiter: RateLimiter,
+}
+
+impl Fetcher {
+    pub fn new(config: Config, root: &Path) -> Self {
+        let limiter = RateLimiter::new(config.min_interval, Some(root.join(STAMP_FILE)));
+        Self { config, root: root.to_path_buf(), limiter }
+    }
+
+    /// Where `resource` of `day` is stored
+    pub fn path(&self, day: u8, resource: Resource) -> PathBuf {
+        self.root.join(format!("src/day{}", day)).join(resource.file_name())
+    }
+
+    /// Is there a non-empty file already? (`new-day` creates empty ones)
+    pub fn is_cached(&self, day: u8, resource: Resource) -> bool {
+        fs::read_to_string(self.path(day, resource)).is_ok_and(|content| !content.trim().is_empty())
+    }
+
+    /// Makes sure `resource` of `day` is on disk, downloading it only if it isn't there yet
+    pub fn fetch(&mut self, day: u8, resource: Resource) -> Result<Fetched, FetchError> {
+        let path = self.path(day, resource);
+        if self.is_cached(day, resource) {
+            debug!("{} is cached", path.display());
+            return Ok(Fetched::Cached(path));
+        }
+
+        let (url, session) = match resource {
+            // the description is public (the session only unlocks part two)
+            Resource::Task => (self.config.task_url(day), self.config.session.as_deref()),
+            Resource::Input => (self.config.input_url(day), Some(self.config.session.as_deref().ok_or(FetchError::NoSession)?)),
+        };
+
+        self.limiter.wait();
+        info!("Fetching {}", url);
+        let response = http::get(&url, session).map_err(FetchError::Transport)?;
+        if !response.is_success() {
+            return Err(FetchError::Status { url, status: response.status });
+        }
+
+        let content = match resource {
+            Resource::Task => html::to_text(&response.body),
+            Resource::Input => response.body,
+        };
+
+        let write = |path: &Path| -> io::Result<()> {
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, content)
+        };
+        write(&path).map_err(|err| FetchError::Write(path.clone(), err))?;
+
+        Ok(Fetched::Downloaded(path))
+    }
+
+    /// Fetches all `resources` of `day` (one result per resource)
+    pub fn fetch_day(&mut self, day: u8, resources: &[Resource]) -> Vec<Result<Fetched, FetchError>> {
+        resources.iter().map(|resource| self.fetch(day, *resource)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::http::Response;
+    use crate::mock_server::MockServer;
+
+    const PAGE: &str = "<main><article><h2>--- Day 3: Binary Diagnostic ---</h2><p>Some &lt;text&gt;.</p></article></main>";
+
+    fn server() -> MockServer {
+        MockServer::start(|request| match (request.path.as_str(), request.header("cookie")) {
+            ("/2021/day/3", _) => Response::new(200, PAGE),
+            ("/2021/day/3/input", Some("session=secret")) => Response::new(200, "00100\n11110\n"),
+            ("/2021/day/3/input", _) => Response::new(400, "Please log in to get your puzzle input."),
+            _ => Response::new(404, "Not Found"),
+        }).unwrap()
+    }
+
+    fn setup(name: &str, server: &MockServer, session: Option<&str>) -> (PathBuf, Fetcher) {
+        let root = std::env::temp_dir().join(format!("aoc_fetch_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        let config = Config {
+            session: session.map(String::from),
+            base_url: server.url(),
+            min_interval: Duration::ZERO,
+            ..Config::default()
+        };
+        (root.clone(), Fetcher::new(config, &root))
+    }
+
+    #[test]
+    fn downloads_once_and_then_uses_the_cache() {
+        let server = server();
+        let (root, mut fetcher) = setup("cache", &server, Some("secret"));
+
+        let fetched = fetcher.fetch_day(3, &Resource::BOTH);
+        assert!(matches!(&fetched[0], Ok(Fetched::Downloaded(_))));
+        assert!(matches!(&fetched[1], Ok(Fetched::Downloaded(_))));
+        assert_eq!(fs::read_to_string(root.join("src/day3/task.txt")).unwrap(), "--- Day 3: Binary Diagnostic ---\n\nSome <text>.\n");
+        assert_eq!(fs::read_to_string(root.join("src/day3/input.txt")).unwrap(), "00100\n11110\n");
+
+        let fetched = fetcher.fetch_day(3, &Resource::BOTH);
+        assert!(fetched.iter().all(|f| matches!(f, Ok(Fetched::Cached(_)))));
+        assert_eq!(server.requests().len(), 2);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn empty_files_are_not_a_cache() {
+        let server = server();
+        let (root, mut fetcher) = setup("empty", &server, Some("secret"));
+        fs::create_dir_all(root.join("src/day3")).unwrap();
+        fs::write(root.join("src/day3/input.txt"), "\n").unwrap();
+
+        assert!(matches!(fetcher.fetch(3, Resource::Input), Ok(Fetched::Downloaded(_))));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_sessions_and_bad_status() {
+        let server = server();
+        let (root, mut fetcher) = setup("errors", &server, None);
+
+        assert!(matches!(fetcher.fetch(3, Resource::Input), Err(FetchError::NoSession)));
+        assert!(matches!(fetcher.fetch(4, Resource::Task), Err(FetchError::Status { status: 404, .. })));
+        assert!(!fetcher.path(4, Resource::Task).exists());
+        assert_eq!(server.requests().len(), 1);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rate_limit_spaces_requests_across_runs() {
+        let stamp = std::env::temp_dir().join(format!("aoc_stamp_{}", std::process::id()));
+        let _ = fs::remove_file(&stamp);
+
+        let mut limiter = RateLimiter::new(Duration::from_millis(100), Some(stamp.clone()));
+        assert_eq!(limiter.remaining(), Duration::ZERO);
+        limiter.wait();
+
+        // a new limiter (like the next run of `aoc`) still knows about the last request
+        let mut next_run = RateLimiter::new(Duration::from_millis(100), Some(stamp.clone()));
+        assert!(!next_run.remaining().is_zero());
+        let start = Instant::now();
+        next_run.wait();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        fs::remove_file(&stamp).unwrap();
+    }
+}
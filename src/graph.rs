@@ -0,0 +1,414 @@
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::{Add, Range};
+
+use crate::grid::Grid;
+
+/// Index of a node within its [Graph]
+pub type NodeId = usize;
+
+/// Nodes of type `N` connected by directed edges with a weight `W`.
+/// Every node is stored only once and referred to by its [NodeId],
+/// so all algorithms work on plain indices instead of hashing the nodes again and again
+#[derive(Debug, Clone)]
+pub struct Graph<N, W = u64> {
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    /// outgoing edges (target, weight) per node
+    edges: Vec<Vec<(NodeId, W)>>,
+}
+
+/// The path walked so far while enumerating paths (see [Graph::for_each_path])
+#[derive(Debug)]
+pub struct Walk<'a> {
+    path: &'a [NodeId],
+    visits: &'a [u32],
+}
+
+impl Walk<'_> {
+    /// All nodes of the path (the first one is the start)
+    pub fn path(&self) -> &[NodeId] {
+        self.path
+    }
+
+    /// How often `id` is on the path
+    pub fn visits(&self, id: NodeId) -> u32 {
+        self.visits[id]
+    }
+}
+
+impl<N: Eq + Hash + Clone, W: Copy> Default for Graph<N, W> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone, W: Copy> Graph<N, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node` (if it isn't there already) and returns its id
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    /// The id of `node`, `None` if it isn't part of the graph
+    pub fn id<Q>(&self, node: &Q) -> Option<NodeId> where N: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.ids.get(node).copied()
+    }
+
+    /// The node behind `id` (panics for ids of other graphs)
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// All node ids
+    pub fn ids(&self) -> Range<NodeId> {
+        0..self.nodes.len()
+    }
+
+    /// Adds a directed edge
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: W) {
+        self.edges[from].push((to, weight));
+    }
+
+    /// Adds both nodes (if needed) and edges in both directions between them
+    pub fn connect(&mut self, a: N, b: N, weight: W) -> (NodeId, NodeId) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.add_edge(a, b, weight);
+        self.add_edge(b, a, weight);
+        (a, b)
+    }
+
+    /// The targets (and weights) of all edges leaving `id`
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, W)> + '_ {
+        self.edges[id].iter().copied()
+    }
+
+    /// All nodes reachable from `start` in breadth first order (`start` first)
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        self.breadth_first(start).0
+    }
+
+    /// The number of edges on the shortest path from `start` to every node (`None` if it can't be reached)
+    pub fn hops(&self, start: NodeId) -> Vec<Option<usize>> {
+        self.breadth_first(start).1
+    }
+
+    fn breadth_first(&self, start: NodeId) -> (Vec<NodeId>, Vec<Option<usize>>) {
+        let mut hops = vec![None; self.len()];
+        let mut order = vec![start];
+        hops[start] = Some(0);
+
+        // `order` doubles as the queue, everything behind `next` still has to be expanded
+        let mut next = 0;
+        while let Some(&id) = order.get(next) {
+            next += 1;
+            let next_hops = hops[id].map(|h| h + 1);
+            for (neighbour, _) in self.neighbours(id) {
+                if hops[neighbour].is_none() {
+                    hops[neighbour] = next_hops;
+                    order.push(neighbour);
+                }
+            }
+        }
+
+        (order, hops)
+    }
+
+    /// All nodes reachable from `start` in depth first order (`start` first)
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+
+        while let Some(id) = stack.pop() {
+            if seen[id] {
+                continue;
+            }
+            seen[id] = true;
+            order.push(id);
+
+            // reversed, so the first neighbour is visited first
+            stack.extend(self.edges[id].iter().rev().map(|(neighbour, _)| *neighbour).filter(|n| !seen[*n]));
+        }
+
+        order
+    }
+
+    /// Groups the nodes into connected components (each in breadth first order).
+    /// This treats the edges as given, so for undirected graphs they should exist in both directions
+    pub fn components(&self) -> Vec<Vec<NodeId>> {
+        let mut assigned = vec![false; self.len()];
+        let mut components = Vec::new();
+
+        for id in self.ids() {
+            if !assigned[id] {
+                let component = self.bfs(id);
+                for member in &component {
+                    assigned[*member] = true;
+                }
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    /// Calls `found` with every path from `start` to `goal` (paths end at `goal`, they don't walk through it).
+    /// The walk may only enter `next` if `allowed(walk, next)` says so; a rule which allows
+    /// going in circles forever will never finish. [simple] gives the usual "no node twice" rule
+    pub fn for_each_path(&self, start: NodeId, goal: NodeId,
+                         allowed: impl Fn(&Walk, NodeId) -> bool, found: impl FnMut(&[NodeId])) {
+        self.for_each_path_with_state(start, goal, (), |walk, _, next| allowed(walk, next).then_some(()), found);
+    }
+
+    /// Like [for_each_path](Self::for_each_path), but every walk carries a small state (starting with `initial`).
+    /// `step(walk, state, next)` returns the state after entering `next`, or `None` if `next` may not be entered.
+    /// Lets a rule remember things about the path (e.g. "some node was visited twice") instead of searching it
+    pub fn for_each_path_with_state<S: Copy>(&self, start: NodeId, goal: NodeId, initial: S,
+                                             step: impl Fn(&Walk, S, NodeId) -> Option<S>, mut found: impl FnMut(&[NodeId])) {
+        let mut path = vec![start];
+        let mut visits = vec![0; self.len()];
+        visits[start] = 1;
+        self.walk(goal, &step, &mut found, &mut path, &mut visits, initial);
+    }
+
+    fn walk<S: Copy>(&self, goal: NodeId, step: &dyn Fn(&Walk, S, NodeId) -> Option<S>, found: &mut dyn FnMut(&[NodeId]),
+                     path: &mut Vec<NodeId>, visits: &mut Vec<u32>, state: S) {
+        let current = *path.last().unwrap();
+        if current == goal {
+            found(path);
+            return;
+        }
+
+        for (next, _) in self.neighbours(current) {
+            if let Some(next_state) = step(&Walk { path, visits }, state, next) {
+                path.push(next);
+                visits[next] += 1;
+                self.walk(goal, step, found, path, visits, next_state);
+                visits[next] -= 1;
+                path.pop();
+            }
+        }
+    }
+
+    /// The number of paths [for_each_path](Self::for_each_path) would find
+    pub fn count_paths(&self, start: NodeId, goal: NodeId, allowed: impl Fn(&Walk, NodeId) -> bool) -> u64 {
+        let mut count = 0;
+        self.for_each_path(start, goal, allowed, |_| count += 1);
+        count
+    }
+
+    /// The number of paths [for_each_path_with_state](Self::for_each_path_with_state) would find
+    pub fn count_paths_with_state<S: Copy>(&self, start: NodeId, goal: NodeId, initial: S,
+                                           step: impl Fn(&Walk, S, NodeId) -> Option<S>) -> u64 {
+        let mut count = 0;
+        self.for_each_path_with_state(start, goal, initial, step, |_| count += 1);
+        count
+    }
+}
+
+/// Visit rule for [Graph::for_each_path]: every node at most once
+pub fn simple(walk: &Walk, next: NodeId) -> bool {
+    walk.visits(next) == 0
+}
+
+impl<N: Eq + Hash + Clone, W: Copy + Ord + Add<Output = W> + Default> Graph<N, W> {
+    /// The cost of the cheapest path from `start` to every node (`None` if it can't be reached).
+    /// Weights must not be negative
+    pub fn dijkstra(&self, start: NodeId) -> Vec<Option<W>> {
+        let mut costs = vec![None; self.len()];
+        let mut queue = BinaryHeap::from([Reverse((W::default(), start))]);
+
+        while let Some(Reverse((cost, id))) = queue.pop() {
+            if costs[id].is_some() {
+                continue;
+            }
+            costs[id] = Some(cost);
+
+            for (neighbour, weight) in self.neighbours(id) {
+                if costs[neighbour].is_none() {
+                    queue.push(Reverse((cost + weight, neighbour)));
+                }
+            }
+        }
+
+        costs
+    }
+
+    /// The cheapest path from `start` to `goal` and its cost. `heuristic` estimates the remaining
+    /// cost from a node to `goal` and must never overestimate it (e.g. the manhattan distance on a grid
+    /// where every step costs at least 1)
+    pub fn astar(&self, start: NodeId, goal: NodeId, heuristic: impl Fn(NodeId) -> W) -> Option<(W, Vec<NodeId>)> {
+        let mut costs: Vec<Option<W>> = vec![None; self.len()];
+        let mut came_from: Vec<Option<NodeId>> = vec![None; self.len()];
+        let mut done = vec![false; self.len()];
+        let mut queue = BinaryHeap::from([Reverse((heuristic(start), start))]);
+        costs[start] = Some(W::default());
+
+        while let Some(Reverse((_, id))) = queue.pop() {
+            if done[id] {
+                continue;
+            }
+            done[id] = true;
+
+            if id == goal {
+                let mut path = vec![goal];
+                while let Some(previous) = came_from[*path.last().unwrap()] {
+                    path.push(previous);
+                }
+                path.reverse();
+                return Some((costs[goal]?, path));
+            }
+
+            let cost = costs[id]?;
+            for (neighbour, weight) in self.neighbours(id) {
+                let new_cost = cost + weight;
+                if !done[neighbour] && costs[neighbour].is_none_or(|known| new_cost < known) {
+                    costs[neighbour] = Some(new_cost);
+                    came_from[neighbour] = Some(id);
+                    queue.push(Reverse((new_cost + heuristic(neighbour), neighbour)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The cheapest path from `start` to `goal` and its cost ([astar](Self::astar) without a heuristic)
+    pub fn shortest_path(&self, start: NodeId, goal: NodeId) -> Option<(W, Vec<NodeId>)> {
+        self.astar(start, goal, |_| W::default())
+    }
+}
+
+impl<W: Copy> Graph<(usize, usize), W> {
+    /// Every cell of `grid` becomes a node (its id is `y * width + x`). There is an edge from each cell
+    /// to each direct neighbour if `weight(from, to)` (given their values) returns one
+    pub fn from_grid<T>(grid: &Grid<T>, weight: impl Fn(&T, &T) -> Option<W>) -> Self {
+        let mut graph = Self::new();
+        for pos in grid.positions() {
+            graph.add_node(pos);
+        }
+
+        for ((x, y), from) in grid.iter() {
+            let id = y * grid.width() + x;
+            for (nx, ny) in grid.neighbours4(x, y) {
+                if let Some(weight) = weight(from, &grid[(nx, ny)]) {
+                    graph.add_edge(id, ny * grid.width() + nx, weight);
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caves() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for (a, b) in [("start", "A"), ("start", "b"), ("A", "c"), ("A", "b"), ("b", "d"), ("A", "end"), ("b", "end")] {
+            graph.connect(a, b, 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn interns_nodes() {
+        let mut graph = caves();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(graph.add_node("A"), graph.id("A").unwrap());
+        assert_eq!(graph.node(graph.id("end").unwrap()), &"end");
+        assert_eq!(graph.id("x"), None);
+    }
+
+    #[test]
+    fn traversals() {
+        let graph = caves();
+        let names = |ids: Vec<NodeId>| ids.into_iter().map(|id| *graph.node(id)).collect::<Vec<_>>();
+        let start = graph.id("start").unwrap();
+
+        assert_eq!(names(graph.bfs(start)), vec!["start", "A", "b", "c", "end", "d"]);
+        assert_eq!(names(graph.dfs(start)), vec!["start", "A", "c", "b", "d", "end"]);
+        assert_eq!(graph.hops(start)[graph.id("d").unwrap()], Some(2));
+    }
+
+    #[test]
+    fn components_of_a_grid() {
+        let grid = Grid::from_digits(&[String::from("1191"), String::from("9911")]).unwrap();
+        let graph: Graph<_> = Graph::from_grid(&grid, |a: &u8, b: &u8| (*a != 9 && *b != 9).then_some(1));
+        let mut sizes: Vec<usize> = graph.components().iter().map(|c| c.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 1, 1, 2, 3]);
+    }
+
+    #[test]
+    fn cheapest_paths() {
+        let grid = Grid::from_digits(&[String::from("116"), String::from("138"), String::from("213")]).unwrap();
+        let graph: Graph<_> = Graph::from_grid(&grid, |_, to: &u64| Some(*to));
+        let goal = graph.id(&(2, 2)).unwrap();
+
+        assert_eq!(graph.dijkstra(0)[goal], Some(7));
+        let (cost, path) = graph.astar(0, goal, |id| {
+            let (x, y) = graph.node(id);
+            ((2 - x) + (2 - y)) as u64
+        }).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path, vec![0, 3, 6, 7, 8]);
+        assert_eq!(graph.shortest_path(0, goal).map(|(cost, _)| cost), Some(7));
+
+        let mut one_way: Graph<u8> = Graph::new();
+        let (a, b) = (one_way.add_node(0), one_way.add_node(1));
+        one_way.add_edge(b, a, 1);
+        assert_eq!(one_way.shortest_path(a, b), None);
+    }
+
+    #[test]
+    fn enumerates_paths_with_visit_rules() {
+        let graph = caves();
+        let (start, end) = (graph.id("start").unwrap(), graph.id("end").unwrap());
+        let big = |id: NodeId| graph.node(id).chars().all(|c| c.is_ascii_uppercase());
+
+        assert_eq!(graph.count_paths(start, end, simple), 4);
+        assert_eq!(graph.count_paths(start, end, |walk, next| big(next) || walk.visits(next) == 0), 10);
+
+        let mut longest = Vec::new();
+        graph.for_each_path(start, end, simple, |path| if path.len() > longest.len() { longest = path.to_vec() });
+        assert_eq!(longest.len(), 4);
+
+        // the state remembers whether a small cave was entered twice already
+        let once_twice = graph.count_paths_with_state(start, end, false, |walk, twice, next| match walk.visits(next) {
+            _ if next == start => None,
+            _ if big(next) => Some(twice),
+            0 => Some(twice),
+            _ => (!twice).then_some(true),
+        });
+        assert_eq!(once_twice, 36);
+    }
+}
@@ -0,0 +1,280 @@
+use std::ops::{Index, IndexMut};
+
+use crate::parse_error::ParseError;
+
+/// Offsets of the direct neighbours (up, right, down, left)
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the direct and the diagonal neighbours
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangular `width` x `height` field of values.
+/// The values are stored row by row in a single vector; positions are `(x, y)`
+/// with `(0, 0)` being the top left corner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid where every cell is `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self where T: Clone {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows. `None` if the rows are not equally wide
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Reads every line as one row, turning each character into a value with `to_value`.
+    /// `expected` describes a valid character for the error message
+    pub fn parse_with(lines: &[String], expected: &str, to_value: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+
+        for (i, line) in lines.iter().enumerate() {
+            let mut row_width = 0;
+            for (pos, c) in line.char_indices() {
+                match to_value(c) {
+                    Some(value) => cells.push(value),
+                    None => return Err(ParseError::at_part(i, line, &line[pos..pos + c.len_utf8()], expected)),
+                }
+                row_width += 1;
+            }
+
+            // all rows have to be equally wide
+            match width {
+                Some(width) if width != row_width =>
+                    return Err(ParseError::at_line(i, line, &format!("{} characters", width))),
+                _ => width = Some(row_width),
+            }
+        }
+
+        match width {
+            Some(width) => Ok(Self { width, height: lines.len(), cells }),
+            None => Err(ParseError::end_of_input(0, expected)),
+        }
+    }
+
+    /// Reads a map of single digits (`'7'` -> `7`)
+    pub fn from_digits(lines: &[String]) -> Result<Self, ParseError> where T: From<u8> {
+        Self::parse_with(lines, "<digit>", |c| c.to_digit(10).map(|digit| T::from(digit as u8)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Is `(x, y)` inside the grid?
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        if self.contains(x, y) {
+            return Some(y * self.width + x);
+        }
+        None
+    }
+
+    /// The value at `(x, y)`, `None` if that's outside of the grid
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(|index| &mut self.cells[index])
+    }
+
+    fn offset_positions(&self, x: usize, y: usize, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(*dx)?;
+            let y = y.checked_add_signed(*dy)?;
+            if self.contains(x, y) { Some((x, y)) } else { None }
+        })
+    }
+
+    /// Positions of the (up to 4) direct neighbours of `(x, y)` which are inside the grid
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &NEIGHBOURS_4)
+    }
+
+    /// Positions of the (up to 8) direct and diagonal neighbours of `(x, y)` which are inside the grid
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &NEIGHBOURS_8)
+    }
+
+    /// All positions, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All values together with their position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// All values, row by row
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// The row `y` (panics if there is no such row)
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// The values of column `x` from top to bottom
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Mirrors the grid along its diagonal: rows become columns
+    pub fn transpose(&self) -> Self where T: Clone {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    /// Applies `f` to every value
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws every row as a string with one character per value
+    pub fn render(&self, to_char: impl Fn(&T) -> char) -> Vec<String> {
+        self.rows().map(|row| row.iter().map(&to_char).collect()).collect()
+    }
+}
+
+impl Grid<char> {
+    /// Reads a map of arbitrary characters
+    pub fn from_chars(lines: &[String]) -> Result<Self, ParseError> {
+        Self::parse_with(lines, "<character>", Some)
+    }
+}
+
+/// `grid[(x, y)]`, panics outside of the grid
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| panic!("({}, {}) is outside of the {}x{} grid", x, y, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| panic!("({}, {}) is outside of the {}x{} grid", x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &[&str]) -> Vec<String> {
+        input.iter().map(|line| String::from(*line)).collect()
+    }
+
+    fn digits() -> Grid<u64> {
+        Grid::from_digits(&lines(&["123", "456"])).unwrap()
+    }
+
+    #[test]
+    fn parses_digits() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get(3, 0), None);
+    }
+
+    #[test]
+    fn reports_bad_characters_and_ragged_rows() {
+        let err = Grid::<u64>::from_digits(&lines(&["12", "1x"])).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+        let err = Grid::<u64>::from_digits(&lines(&["12", "123"])).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(Grid::<u64>::from_digits(&[]).is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = digits();
+        let mut corner: Vec<_> = grid.neighbours4(0, 0).collect();
+        corner.sort();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours4(1, 0).count(), 3);
+        assert_eq!(grid.neighbours8(0, 0).count(), 3);
+        assert_eq!(grid.neighbours8(1, 1).count(), 5);
+    }
+
+    #[test]
+    fn rows_columns_and_transposition() {
+        let grid = digits();
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u64>>(), vec![2, 5]);
+
+        let transposed = grid.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (2, 3));
+        assert_eq!(transposed.rows().collect::<Vec<_>>(), vec![&[1, 4][..], &[2, 5], &[3, 6]]);
+        assert_eq!(transposed.transpose(), grid);
+    }
+
+    #[test]
+    fn renders_characters() {
+        let grid = Grid::from_chars(&lines(&["#.", ".#"])).unwrap();
+        assert_eq!(grid.render(|c| if *c == '#' { '#' } else { ' ' }), vec!["# ", " #"]);
+        assert_eq!(grid.iter().filter(|(_, c)| **c == '#').map(|(pos, _)| pos).collect::<Vec<_>>(),
+                   vec![(0, 0), (1, 1)]);
+    }
+}
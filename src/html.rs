@@ -0,0 +1,188 @@
+/// A piece of HTML: either a tag (`<p>`, `</p>`, ...) or the text between tags
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// lower case name of the tag and whether it is a closing one
+    Tag(String, bool),
+    Text(&'a str),
+}
+
+/// Splits `html` into tags and text. Comments are dropped
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').unwrap_or(rest.len() - 1);
+            let inner = &rest[1..end];
+            let closing = inner.starts_with('/');
+            let name = inner
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            tokens.push(Token::Tag(name, closing));
+            rest = &rest[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+/// Replaces the entities the puzzles use (`&lt;`, `&#39;`, ...) by their characters
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded += &rest[..start];
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let character = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()).and_then(char::from_u32),
+            },
+        });
+
+        match (character, entity) {
+            (Some(character), Some(entity)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+
+    decoded + rest
+}
+
+/// Collapses all whitespace (including line breaks) to single spaces, like a browser does outside of `<pre>`
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Turns a puzzle page into the text a browser would show (the format of the `task.txt` files):
+/// only the puzzle descriptions (`<article>`) and the `Your puzzle answer was ...` lines are kept,
+/// headings and paragraphs are separated by blank lines, `<pre>` blocks are kept as they are and
+/// list items are indented by four spaces
+pub fn to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_article = false;
+    let mut in_pre = false;
+    // the text of the current block (heading, paragraph, list item, ...)
+    let mut block: Option<String> = None;
+
+    for token in tokens(html) {
+        match token {
+            Token::Tag(name, false) => match name.as_str() {
+                "article" => in_article = true,
+                "pre" if in_article => {
+                    in_pre = true;
+                    block = Some(String::new());
+                },
+                "h2" | "p" | "li" => block = Some(String::new()),
+                _ => {},
+            },
+            Token::Tag(name, true) => match name.as_str() {
+                "article" => in_article = false,
+                "pre" if in_pre => {
+                    in_pre = false;
+                    let content = block.take().unwrap_or_default();
+                    text += content.trim_end_matches('\n');
+                    text += "\n\n";
+                },
+                "h2" | "p" if in_article => {
+                    text += &collapse_whitespace(&block.take().unwrap_or_default());
+                    text += "\n\n";
+                },
+                // the answers are not part of the article
+                "p" => {
+                    let content = collapse_whitespace(&block.take().unwrap_or_default());
+                    if content.starts_with("Your puzzle answer was") {
+                        text += &content;
+                        text += "\n";
+                    }
+                },
+                "li" if in_article => {
+                    text += "    ";
+                    text += &collapse_whitespace(&block.take().unwrap_or_default());
+                    text += "\n";
+                },
+                "ul" if in_article => text += "\n",
+                _ => {},
+            },
+            Token::Text(content) => {
+                if let Some(block) = block.as_mut() {
+                    *block += &decode_entities(content);
+                }
+            },
+        }
+    }
+
+    String::from(text.trim_end()) + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(decode_entities("0,9 -&gt; 5,9 &amp; &#39;x&#x27; &unknown; & done"), "0,9 -> 5,9 & 'x' &unknown; & done");
+    }
+
+    #[test]
+    fn converts_a_puzzle_page() {
+        let html = r#"<html><body><header>Advent of Code</header><main>
+<article class="day-desc"><h2>--- Day 1: Sonar Sweep ---</h2><p>For example, suppose you had the
+following <em>report</em>:</p>
+<pre><code>199
+200
+</code></pre>
+<ul><li>An entry like <code>1,1 -&gt; 1,3</code>.</li><li>Another one.</li></ul>
+<p>How many?</p>
+</article>
+<p>Your puzzle answer was <code>1583</code>.</p><article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<!-- <p>hidden</p> -->
+<p>Consider sums.</p></article>
+<p>Both parts of this puzzle are complete!</p><p>You can also <a href="x">share</a> this puzzle.</p>
+</main></body></html>"#;
+
+        assert_eq!(to_text(html), "\
+--- Day 1: Sonar Sweep ---
+
+For example, suppose you had the following report:
+
+199
+200
+
+    An entry like 1,1 -> 1,3.
+    Another one.
+
+How many?
+
+Your puzzle answer was 1583.
+--- Part Two ---
+
+Consider sums.
+");
+    }
+}
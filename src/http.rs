@@ -0,0 +1,246 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Sent with every request so the server knows who is asking
+pub const USER_AGENT: &str = "github.com/Mereep/advent_of_code_2021_rust (aoc)";
+
+/// How long we wait for a server before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The parts of a URL like `http://127.0.0.1:8080/2021/day/1` we need
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    /// `http` or `https`
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+    /// everything after the host, at least `/`
+    pub path: String,
+}
+
+impl Url {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let (scheme, rest) = url.split_once("://").ok_or(format!("{} is not a URL", url))?;
+        let default_port = match scheme {
+            "http" => 80,
+            "https" => 443,
+            _ => return Err(format!("Unsupported scheme {} in {}", scheme, url)),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(pos) => (&rest[..pos], &rest[pos..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().map_err(|_| format!("Invalid port in {}", url))?),
+            None => (authority, default_port),
+        };
+        if host.is_empty() {
+            return Err(format!("{} has no host", url));
+        }
+
+        Ok(Self {
+            scheme: String::from(scheme),
+            host: String::from(host),
+            port,
+            path: String::from(path),
+        })
+    }
+}
+
+/// What the server answered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn new(status: u16, body: &str) -> Self {
+        Self { status, body: String::from(body) }
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends a GET request to `url`. If given, `session` is sent as the `session` cookie.
+/// Plain `http` is spoken directly, `https` is handed to `curl`
+pub fn get(url: &str, session: Option<&str>) -> Result<Response, String> {
+    send(url, session, None)
+}
+
+/// Sends a POST request with `fields` as form data (`application/x-www-form-urlencoded`)
+pub fn post_form(url: &str, session: Option<&str>, fields: &[(&str, &str)]) -> Result<Response, String> {
+    send(url, session, Some(&form_encode(fields)))
+}
+
+/// `a=1&b=x%20y`
+pub fn form_encode(fields: &[(&str, &str)]) -> String {
+    let encode = |text: &str| text
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => String::from(b as char),
+            _ => format!("%{:02X}", b),
+        })
+        .collect::<String>();
+
+    fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+/// A GET request without `form`, otherwise a POST of the (already encoded) form
+fn send(url: &str, session: Option<&str>, form: Option<&str>) -> Result<Response, String> {
+    let url = Url::parse(url)?;
+    match url.scheme.as_str() {
+        "http" => send_plain(&url, session, form),
+        _ => send_with_curl(&url, session, form),
+    }
+}
+
+/// Speaks HTTP/1.1 over a plain TCP connection (e.g. to the mock server)
+fn send_plain(url: &Url, session: Option<&str>, form: Option<&str>) -> Result<Response, String> {
+    let failed = |err: std::io::Error| format!("Request to {}:{} failed: {}", url.host, url.port, err);
+
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port)).map_err(failed)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(failed)?;
+
+    let method = if form.is_some() { "POST" } else { "GET" };
+    let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nConnection: close\r\n",
+                              method, url.path, url.host, USER_AGENT);
+    if let Some(session) = session {
+        request += &format!("Cookie: session={}\r\n", session);
+    }
+    if let Some(form) = form {
+        request += &format!("Content-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n", form.len());
+    }
+    request += "\r\n";
+    request += form.unwrap_or_default();
+    stream.write_all(request.as_bytes()).map_err(failed)?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).map_err(failed)?;
+    parse_response(&raw)
+}
+
+/// Lets `curl` do the TLS. Cookie and form are passed via stdin so they don't show up in the process list
+fn send_with_curl(url: &Url, session: Option<&str>, form: Option<&str>) -> Result<Response, String> {
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--user-agent", USER_AGENT, "--config", "-", "--write-out", "\n%{http_code}"])
+        .arg(format!("{}://{}:{}{}", url.scheme, url.host, url.port, url.path))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Couldn't start curl (needed for https): {}", err))?;
+
+    let mut config = String::new();
+    if let Some(session) = session {
+        config += &format!("header = \"Cookie: session={}\"\n", session);
+    }
+    // form encoded data contains no quotes or backslashes
+    if let Some(form) = form {
+        config += &format!("data = \"{}\"\n", form);
+    }
+    curl.stdin.take().unwrap().write_all(config.as_bytes())
+        .map_err(|err| format!("Couldn't talk to curl: {}", err))?;
+
+    let output = curl.wait_with_output().map_err(|err| format!("curl failed: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    // the status code is written after the body
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').ok_or("curl returned no status code")?;
+    let status = status.trim().parse::<u16>().map_err(|_| format!("curl returned a strange status {}", status))?;
+    Ok(Response { status, body: String::from(body) })
+}
+
+/// Splits a raw HTTP response into status and body (handling chunked bodies)
+fn parse_response(raw: &[u8]) -> Result<Response, String> {
+    let head_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("The server's response has no end of header")?;
+    let head = String::from_utf8_lossy(&raw[..head_end]);
+    let mut body = raw[head_end + 4..].to_vec();
+
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or(format!("Invalid status line {}", status_line))?;
+
+    let chunked = lines.any(|line| match line.split_once(':') {
+        Some((name, value)) => name.eq_ignore_ascii_case("transfer-encoding") && value.trim().eq_ignore_ascii_case("chunked"),
+        None => false,
+    });
+    if chunked {
+        body = dechunk(&body)?;
+    }
+
+    Ok(Response { status, body: String::from_utf8_lossy(&body).into_owned() })
+}
+
+/// Joins the chunks of a `Transfer-Encoding: chunked` body
+fn dechunk(mut raw: &[u8]) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = raw.windows(2).position(|w| w == b"\r\n").ok_or("Truncated chunk")?;
+        let size_text = String::from_utf8_lossy(&raw[..line_end]);
+        let size = usize::from_str_radix(size_text.split(';').next().unwrap_or_default().trim(), 16)
+            .map_err(|_| format!("Invalid chunk size {}", size_text))?;
+        if size == 0 {
+            return Ok(body);
+        }
+
+        let chunk = raw.get(line_end + 2..line_end + 2 + size).ok_or("Truncated chunk")?;
+        body.extend_from_slice(chunk);
+        raw = raw.get(line_end + 4 + size..).ok_or("Truncated chunk")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_urls() {
+        assert_eq!(Url::parse("http://127.0.0.1:8080/2021/day/1"), Ok(Url {
+            scheme: String::from("http"),
+            host: String::from("127.0.0.1"),
+            port: 8080,
+            path: String::from("/2021/day/1"),
+        }));
+        let url = Url::parse("https://adventofcode.com").unwrap();
+        assert_eq!((url.port, url.path.as_str()), (443, "/"));
+        assert!(Url::parse("ftp://example.com").is_err());
+        assert!(Url::parse("adventofcode.com").is_err());
+    }
+
+    #[test]
+    fn parses_responses() {
+        let response = parse_response(b"HTTP/1.1 404 Not Found\r\nContent-Length: 3\r\n\r\nno!").unwrap();
+        assert_eq!(response, Response::new(404, "no!"));
+        assert!(!response.is_success());
+
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1,2,\r\n3\r\n3,4\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked).unwrap().body, "1,2,3,4");
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+
+    #[test]
+    fn encodes_forms() {
+        assert_eq!(form_encode(&[("level", "1"), ("answer", "a b&c=ü")]), "level=1&answer=a%20b%26c%3D%C3%BC");
+    }
+}
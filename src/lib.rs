@@ -0,0 +1,41 @@
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod bench;
+pub mod bignum;
+pub mod cli;
+pub mod config;
+pub mod examples;
+pub mod expected;
+pub mod fetch;
+pub mod graph;
+pub mod grid;
+pub mod html;
+pub mod http;
+pub mod loader;
+pub mod log;
+pub mod memory;
+pub mod mock_server;
+pub mod output;
+pub mod parser;
+pub mod parse_error;
+pub mod pool;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod sparse_grid;
+pub mod submit;
+pub mod verify;
+pub mod watch;
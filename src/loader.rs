@@ -0,0 +1,143 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Where some input came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+/// Everything that can go wrong while loading an input
+#[derive(Debug)]
+pub enum LoadError {
+    /// the file does not exist
+    Missing(PathBuf),
+    /// the file (or stdin) exists but couldn't be read
+    Unreadable(Source, io::Error),
+    /// the content is not valid UTF-8; `valid_up_to` is the byte offset of the first bad byte
+    NotUtf8 { source: Source, valid_up_to: usize },
+    /// there is nothing but whitespace in there
+    Empty(Source),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(path) => write!(f, "Input file {} does not exist", path.display()),
+            Self::Unreadable(source, err) => write!(f, "Couldn't read {}: {}", source, err),
+            Self::NotUtf8 { source, valid_up_to } =>
+                write!(f, "{} is not valid UTF-8 (first bad byte at offset {})", source, valid_up_to),
+            Self::Empty(source) => write!(f, "{} is empty", source),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Loaded input split into lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub source: Source,
+    pub lines: Vec<String>,
+}
+
+impl Input {
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+/// Splits `content` into lines, optionally trimming whitespace on both ends of each line
+fn to_lines(content: &str, trim: bool) -> Vec<String> {
+    content.lines().map(|line| {
+        if trim {
+            String::from(line.trim())
+        } else {
+            String::from(line)
+        }
+    }).collect()
+}
+
+/// Reads everything from `reader` and turns it into an [Input]
+pub fn load_reader(mut reader: impl Read, source: Source, trim: bool) -> Result<Input, LoadError> {
+    let mut bytes = Vec::new();
+    if let Err(err) = reader.read_to_end(&mut bytes) {
+        return Err(LoadError::Unreadable(source, err));
+    }
+
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(err) => return Err(LoadError::NotUtf8 {
+            source,
+            valid_up_to: err.utf8_error().valid_up_to()
+        }),
+    };
+
+    if content.trim().is_empty() {
+        return Err(LoadError::Empty(source));
+    }
+
+    Ok(Input {
+        lines: to_lines(&content, trim),
+        source,
+    })
+}
+
+/// Loads the file at `path`
+pub fn load_file(path: &Path, trim: bool) -> Result<Input, LoadError> {
+    let source = Source::File(path.to_path_buf());
+    match fs::File::open(path) {
+        Ok(file) => load_reader(file, source, trim),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(LoadError::Missing(path.to_path_buf())),
+        Err(err) => Err(LoadError::Unreadable(source, err)),
+    }
+}
+
+/// Loads whatever is piped into the program
+pub fn load_stdin(trim: bool) -> Result<Input, LoadError> {
+    load_reader(io::stdin().lock(), Source::Stdin, trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_lines_if_asked_to() {
+        let input = load_reader("  1 \n2\n".as_bytes(), Source::Stdin, true).unwrap();
+        assert_eq!(input.lines(), &[String::from("1"), String::from("2")]);
+
+        let input = load_reader("  1 \n2\n".as_bytes(), Source::Stdin, false).unwrap();
+        assert_eq!(input.lines(), &[String::from("  1 "), String::from("2")]);
+    }
+
+    #[test]
+    fn rejects_whitespace_only_input() {
+        assert!(matches!(load_reader(" \n\n".as_bytes(), Source::Stdin, true), Err(LoadError::Empty(_))));
+    }
+
+    #[test]
+    fn reports_where_utf8_breaks() {
+        let bytes: &[u8] = &[b'a', b'b', 0xff];
+        assert!(matches!(load_reader(bytes, Source::Stdin, true),
+                         Err(LoadError::NotUtf8 { valid_up_to: 2, .. })));
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let path = Path::new("./src/day0/does_not_exist.txt");
+        assert!(matches!(load_file(path, true), Err(LoadError::Missing(_))));
+    }
+}
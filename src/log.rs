@@ -0,0 +1,84 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How chatty the program is. Every level includes the ones before it.
+/// Everything logged goes to stderr, stdout is reserved for the answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// things that went wrong (always shown, even with `-q`)
+    Error = 0,
+    /// things that look fishy (shown by default)
+    Warn = 1,
+    /// what is being executed and interesting intermediate results (`-v`)
+    Info = 2,
+    /// dumps of whole data structures (`-vv`)
+    Debug = 3,
+}
+
+impl Level {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Error,
+            1 => Self::Warn,
+            2 => Self::Info,
+            _ => Self::Debug,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+/// Sets the most detailed level which is still shown
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    Level::from_u8(LEVEL.load(Ordering::Relaxed))
+}
+
+/// Would a message of `level` be shown?
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Writes the message to stderr. Use the macros ([error], [warning], [info], [debug]) instead,
+/// they don't even format the message if the level is disabled
+pub fn write(level: Level, args: fmt::Arguments) {
+    eprintln!("[{}] {}", level.label(), args);
+}
+
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Error, $($arg)*) };
+}
+
+macro_rules! warning {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Warn, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Info, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::log!($crate::log::Level::Debug, $($arg)*) };
+}
+
+pub(crate) use {debug, error, info, log, warning};
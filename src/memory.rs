@@ -0,0 +1,158 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What a piece of code allocated on the heap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// the most bytes that were in use at the same time (on top of what was in use before)
+    pub peak: usize,
+    /// all bytes allocated, a growing `Vec` counts every reallocation
+    pub total: usize,
+    /// number of allocations (and reallocations)
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    /// can be negative if memory allocated before the measurement is freed
+    current: isize,
+    usage: MemoryUsage,
+}
+
+thread_local! {
+    // no destructor and a const initializer, so it can be used from within the allocator
+    static COUNTERS: Cell<Counters> = const { Cell::new(Counters { current: 0, usage: MemoryUsage { peak: 0, total: 0, count: 0 } }) };
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Wraps the system allocator and counts what every thread allocates (once [enable]d).
+/// Only active if a binary installs it:
+/// ```text
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+/// ```
+/// The counters are per thread, so days running in parallel don't mix up their numbers
+pub struct CountingAllocator;
+
+fn record(allocated: usize, freed: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        c.current += allocated as isize - freed as isize;
+        if allocated > 0 {
+            c.usage.total += allocated;
+            c.usage.count += 1;
+        }
+        c.usage.peak = c.usage.peak.max(c.current.max(0) as usize);
+        counters.set(c);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Ordering::Relaxed) {
+            record(0, layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting. Returns `false` if the [CountingAllocator] isn't installed (nothing would be counted)
+pub fn enable() -> bool {
+    ENABLED.store(true, Ordering::Relaxed);
+    let (_, usage) = measure(|| std::hint::black_box(Box::new(0u64)));
+    usage.count > 0
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f` and returns what it allocated on the current thread
+/// (threads spawned by `f` are not counted). Measurements must not be nested
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, MemoryUsage) {
+    COUNTERS.with(|counters| counters.set(Counters::default()));
+    let result = f();
+    let usage = COUNTERS.with(|counters| counters.get().usage);
+    (result, usage)
+}
+
+/// `512 B`, `1.5 KiB`, `3.2 MiB`, ...
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn counts_what_a_closure_allocates() {
+        assert!(enable());
+
+        let (sum, usage) = measure(|| {
+            let mut numbers: Vec<u64> = Vec::with_capacity(1000);
+            numbers.extend(0..1000);
+            let small = vec![1u8; 100].into_boxed_slice();
+            numbers.iter().sum::<u64>() + small.len() as u64
+        });
+        assert_eq!(sum, 499600);
+        assert_eq!(usage.count, 2);
+        assert_eq!(usage.total, 8100);
+        assert_eq!(usage.peak, 8100);
+
+        // freed memory doesn't count towards the peak twice
+        let (_, usage) = measure(|| {
+            for _ in 0..10 {
+                drop(std::hint::black_box(vec![0u8; 1000]));
+            }
+        });
+        assert_eq!((usage.count, usage.total, usage.peak), (10, 10000, 1000));
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 + 200 * 1024), "3.2 MiB");
+    }
+}
@@ -0,0 +1,189 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::http::Response;
+use crate::log::{info, warning};
+
+/// A request the mock server received
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl ReceivedRequest {
+    /// The value of header `name` (case insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Answers a request
+pub type Handler = dyn Fn(&ReceivedRequest) -> Response + Send + Sync;
+
+/// A tiny HTTP server standing in for adventofcode.com, so fetching can be tried (and tested) offline.
+/// It runs in a background thread until the program ends and remembers every request
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl MockServer {
+    /// Starts serving on a free port of 127.0.0.1
+    pub fn start(handler: impl Fn(&ReceivedRequest) -> Response + Send + Sync + 'static) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        thread::spawn(move || serve(listener, &handler, Some(&*log)));
+
+        Ok(Self { addr, requests })
+    }
+
+    /// The base URL to configure, e.g. `http://127.0.0.1:41234`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// All requests received so far
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Answers requests on `listener` forever (one after the other), optionally remembering them in `log`
+pub fn serve(listener: TcpListener, handler: &Handler, log: Option<&Mutex<Vec<ReceivedRequest>>>) {
+    for stream in listener.incoming() {
+        let result = stream.and_then(|mut stream| {
+            let request = read_request(&mut stream)?;
+            info!("{} {}", request.method, request.path);
+            let response = handler(&request);
+            if let Some(log) = log {
+                log.lock().unwrap().push(request);
+            }
+            write_response(&mut stream, &response)
+        });
+
+        if let Err(err) = result {
+            warning!("Mock server: {}", err);
+        }
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> io::Result<ReceivedRequest> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (String::from(method), String::from(path)),
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid request line")),
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        match line.trim_end().split_once(':') {
+            Some((name, value)) => headers.push((String::from(name.trim()), String::from(value.trim()))),
+            None => break,
+        }
+    }
+
+    let mut request = ReceivedRequest { method, path, headers, body: String::new() };
+    let length = request.header("content-length").and_then(|l| l.parse::<usize>().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    request.body = String::from_utf8_lossy(&body).into_owned();
+
+    Ok(request)
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        302 => "Found",
+        400 => "Bad Request",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    };
+    write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+           response.status, reason, response.body.len(), response.body)?;
+    stream.flush()
+}
+
+/// A handler serving the files below `dir`: `/2021/day/1/input` is answered with `dir/2021/day/1/input`,
+/// a directory with its `index.html`. Everything else (or anything trying to leave `dir`) is a 404
+pub fn serve_dir(dir: PathBuf) -> impl Fn(&ReceivedRequest) -> Response + Send + Sync {
+    move |request| {
+        let relative = Path::new(request.path.split('?').next().unwrap_or_default().trim_start_matches('/'));
+        if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Response::new(404, "Not Found");
+        }
+
+        let mut path = dir.join(relative);
+        if path.is_dir() {
+            path = path.join("index.html");
+        }
+        match fs::read_to_string(&path) {
+            Ok(content) => Response::new(200, &content),
+            Err(_) => Response::new(404, "Not Found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http;
+
+    #[test]
+    fn answers_and_records_requests() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/hello" => Response::new(200, "hi"),
+            _ => Response::new(404, "nope"),
+        }).unwrap();
+
+        assert_eq!(http::get(&format!("{}/hello", server.url()), Some("abc")), Ok(Response::new(200, "hi")));
+        assert_eq!(http::get(&format!("{}/other", server.url()), None).unwrap().status, 404);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].header("cookie"), Some("session=abc"));
+        assert_eq!(requests[1].header("Cookie"), None);
+    }
+
+    #[test]
+    fn serves_a_directory() {
+        let dir = std::env::temp_dir().join(format!("aoc_mock_{}", std::process::id()));
+        fs::create_dir_all(dir.join("2021/day/1")).unwrap();
+        fs::write(dir.join("2021/day/1/index.html"), "<article>1</article>").unwrap();
+        fs::write(dir.join("2021/day/1/input"), "199\n200\n").unwrap();
+
+        let handler = serve_dir(dir.clone());
+        let request = |path: &str| handler(&ReceivedRequest {
+            method: String::from("GET"),
+            path: String::from(path),
+            headers: Vec::new(),
+            body: String::new(),
+        });
+
+        assert_eq!(request("/2021/day/1"), Response::new(200, "<article>1</article>"));
+        assert_eq!(request("/2021/day/1/input").body, "199\n200\n");
+        assert_eq!(request("/2021/day/2").status, 404);
+        assert_eq!(request("/../etc/passwd").status, 404);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
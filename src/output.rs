@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Duration;
+
+use crate::memory::MemoryUsage;
+use crate::runner::{self, InputKind, RunResult};
+use crate::solution::{Answer, Part};
+
+/// How run results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// aligned plain text for humans
+    Table,
+    /// one JSON object per line
+    Json,
+    /// comma separated values with a header line
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(Self::Table),
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Column names of the CSV output (and keys of the JSON objects)
+pub const FIELDS: [&str; 7] = ["day", "part", "input", "answer", "answer_type", "elapsed_ns", "error"];
+
+/// Added after [FIELDS] when memory was profiled
+pub const MEMORY_FIELDS: [&str; 3] = ["peak_bytes", "allocated_bytes", "allocations"];
+
+fn memory_values(memory: &MemoryUsage) -> [String; 3] {
+    [memory.peak.to_string(), memory.total.to_string(), memory.count.to_string()]
+}
+
+/// Quotes `value` as a JSON string
+fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\r' => quoted += "\\r",
+            '\t' => quoted += "\\t",
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Integers become numbers, text a string and ASCII art an array of its rows
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(value) => value.to_string(),
+        Answer::Text(value) => json_string(value),
+        Answer::Art(rows) => format!("[{}]", rows
+            .iter()
+            .map(|row| json_string(row))
+            .collect::<Vec<String>>()
+            .join(",")),
+    }
+}
+
+/// One JSON object (without a trailing newline). Failed runs have `null` as answer and answer type
+pub fn to_json(result: &RunResult) -> String {
+    let (answer, answer_type, error) = match &result.outcome {
+        Ok(answer) => (json_answer(answer), json_string(answer.kind()), String::from("null")),
+        Err(msg) => (String::from("null"), String::from("null"), json_string(msg)),
+    };
+
+    let values = [
+        result.day.to_string(),
+        result.part.to_string(),
+        json_string(&result.input.to_string()),
+        answer,
+        answer_type,
+        result.elapsed.as_nanos().to_string(),
+        error,
+    ];
+
+    let mut members = FIELDS
+        .iter()
+        .zip(values.iter())
+        .map(|(key, value)| format!("{}:{}", json_string(key), value))
+        .collect::<Vec<String>>();
+    if let Some(memory) = &result.memory {
+        members.extend(MEMORY_FIELDS
+            .iter()
+            .zip(memory_values(memory))
+            .map(|(key, value)| format!("{}:{}", json_string(key), value)));
+    }
+    format!("{{{}}}", members.join(","))
+}
+
+/// The values [to_json] writes; just enough JSON to read them back
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonValue {
+    Null,
+    Number(u128),
+    String(String),
+    Array(Vec<JsonValue>),
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+        None => Err(format!("expected `{}`, found the end", expected)),
+    }
+}
+
+/// Reads a string, the opening quote is still in `chars`
+fn read_json_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect(chars, '"')?;
+    let mut value = String::new();
+    loop {
+        match chars.next().ok_or("unterminated string")? {
+            '"' => return Ok(value),
+            '\\' => match chars.next().ok_or("unterminated string")? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    let c = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32)
+                        .ok_or(format!("invalid escape \\u{}", code))?;
+                    value.push(c);
+                },
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+fn read_json_value(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    match chars.peek() {
+        Some('"') => Ok(JsonValue::String(read_json_string(chars)?)),
+        Some('n') => {
+            let word: String = chars.by_ref().take(4).collect();
+            if word == "null" { Ok(JsonValue::Null) } else { Err(format!("unknown value {}", word)) }
+        },
+        Some('[') => {
+            chars.next();
+            let mut items = Vec::new();
+            while chars.peek() != Some(&']') {
+                if !items.is_empty() {
+                    expect(chars, ',')?;
+                }
+                items.push(read_json_value(chars)?);
+            }
+            chars.next();
+            Ok(JsonValue::Array(items))
+        },
+        Some(c) if c.is_ascii_digit() => {
+            let mut digits = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                digits.push(c);
+            }
+            digits.parse().map(JsonValue::Number).map_err(|_| format!("number {} is too large", digits))
+        },
+        Some(c) => Err(format!("unexpected `{}`", c)),
+        None => Err(String::from("unexpected end")),
+    }
+}
+
+/// Reads a line written by [to_json] back into a result
+pub fn from_json(line: &str) -> Result<RunResult, String> {
+    let mut chars = line.trim().chars().peekable();
+    let mut members = HashMap::new();
+    expect(&mut chars, '{')?;
+    while chars.peek() != Some(&'}') {
+        if !members.is_empty() {
+            expect(&mut chars, ',')?;
+        }
+        let key = read_json_string(&mut chars)?;
+        expect(&mut chars, ':')?;
+        members.insert(key, read_json_value(&mut chars)?);
+    }
+    expect(&mut chars, '}')?;
+
+    let field = |key: &str| members.get(key).ok_or(format!("`{}` is missing", key));
+    let number = |key: &str| match field(key)? {
+        JsonValue::Number(n) => Ok(*n),
+        other => Err(format!("`{}` should be a number, not {:?}", key, other)),
+    };
+    let string = |key: &str| match field(key)? {
+        JsonValue::String(s) => Ok(Some(s.clone())),
+        JsonValue::Null => Ok(None),
+        other => Err(format!("`{}` should be a string, not {:?}", key, other)),
+    };
+
+    let outcome = match (string("error")?, string("answer_type")?.as_deref(), field("answer")?) {
+        (Some(error), _, _) => Err(error),
+        (None, Some("integer"), JsonValue::Number(n)) => Ok(Answer::Integer(u64::try_from(*n).map_err(|_| "answer too large")?)),
+        (None, Some("text"), JsonValue::String(text)) => Ok(Answer::Text(text.clone())),
+        (None, Some("art"), JsonValue::Array(rows)) => Ok(Answer::Art(rows
+            .iter()
+            .map(|row| match row {
+                JsonValue::String(row) => Ok(row.clone()),
+                other => Err(format!("a row should be a string, not {:?}", other)),
+            })
+            .collect::<Result<Vec<String>, String>>()?)),
+        (None, kind, answer) => return Err(format!("answer {:?} doesn't fit answer_type {:?}", answer, kind)),
+    };
+
+    Ok(RunResult {
+        day: u8::try_from(number("day")?).map_err(|_| "invalid day")?,
+        part: u8::try_from(number("part")?).ok().and_then(Part::from_number).ok_or("invalid part")?,
+        input: InputKind::from_arg(&string("input")?.ok_or("`input` is null")?),
+        outcome,
+        elapsed: Duration::from_nanos(u64::try_from(number("elapsed_ns")?).unwrap_or(u64::MAX)),
+        memory: match members.contains_key(MEMORY_FIELDS[0]) {
+            true => Some(MemoryUsage {
+                peak: number(MEMORY_FIELDS[0])? as usize,
+                total: number(MEMORY_FIELDS[1])? as usize,
+                count: number(MEMORY_FIELDS[2])? as usize,
+            }),
+            false => None,
+        },
+    })
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+    String::from(value)
+}
+
+/// The CSV header line (without a trailing newline)
+pub fn csv_header() -> String {
+    FIELDS.join(",")
+}
+
+/// One CSV record (without a trailing newline).
+/// ASCII art keeps its rows separated by line breaks inside a quoted field.
+/// Profiled results get the [MEMORY_FIELDS] as additional columns
+pub fn to_csv(result: &RunResult) -> String {
+    let (answer, answer_type, error) = match &result.outcome {
+        Ok(answer) => (answer.to_string(), answer.kind(), ""),
+        Err(msg) => (String::new(), "", msg.as_str()),
+    };
+
+    let mut fields = vec![
+        result.day.to_string(),
+        result.part.to_string(),
+        csv_field(&result.input.to_string()),
+        csv_field(&answer),
+        String::from(answer_type),
+        result.elapsed.as_nanos().to_string(),
+        csv_field(error),
+    ];
+    if let Some(memory) = &result.memory {
+        fields.extend(memory_values(memory));
+    }
+    fields.join(",")
+}
+
+/// Writes all results in `format` to stdout
+pub fn print(results: &[RunResult], format: OutputFormat) {
+    match format {
+        OutputFormat::Table => runner::print_table(results),
+        OutputFormat::Json => {
+            for result in results {
+                println!("{}", to_json(result));
+            }
+        },
+        OutputFormat::Csv => {
+            match results.iter().any(|result| result.memory.is_some()) {
+                true => println!("{},{}", csv_header(), MEMORY_FIELDS.join(",")),
+                false => println!("{}", csv_header()),
+            }
+            for result in results {
+                println!("{}", to_csv(result));
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(outcome: Result<Answer, String>) -> RunResult {
+        RunResult {
+            day: 13,
+            part: Part::Two,
+            input: InputKind::Test,
+            outcome,
+            elapsed: Duration::from_micros(12),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn json_of_an_integer() {
+        assert_eq!(to_json(&result(Ok(Answer::Integer(17)))),
+                   r##"{"day":13,"part":2,"input":"test","answer":17,"answer_type":"integer","elapsed_ns":12000,"error":null}"##);
+    }
+
+    #[test]
+    fn json_of_art_is_an_array_of_rows() {
+        let art = Answer::Art(vec![String::from("# #"), String::from(" \"")]);
+        assert!(to_json(&result(Ok(art))).contains(r##""answer":["# #"," \""],"answer_type":"art""##));
+    }
+
+    #[test]
+    fn json_of_a_failure() {
+        assert!(to_json(&result(Err(String::from("line 1\tbroken"))))
+            .ends_with(r##""answer":null,"answer_type":null,"elapsed_ns":12000,"error":"line 1\tbroken"}"##));
+    }
+
+    #[test]
+    fn json_reads_back() {
+        let outcomes = [
+            Ok(Answer::Integer(26984457539)),
+            Ok(Answer::Text(String::from("a \"quoted\" \\ text\u{1}"))),
+            Ok(Answer::Art(vec![String::from("# #"), String::from("")])),
+            Err(String::from("line 1\tbroken\n")),
+        ];
+        for outcome in outcomes {
+            let written = result(outcome.clone());
+            let read = from_json(&to_json(&written)).unwrap();
+            assert_eq!((read.day, read.part, read.input, read.outcome, read.elapsed),
+                       (13, Part::Two, InputKind::Test, outcome, Duration::from_micros(12)));
+        }
+        assert!(from_json(r#"{"day":1}"#).is_err());
+
+        let profiled = RunResult { memory: Some(MemoryUsage { peak: 1024, total: 4096, count: 3 }), ..result(Ok(Answer::Integer(1))) };
+        let json = to_json(&profiled);
+        assert!(json.ends_with(r##""error":null,"peak_bytes":1024,"allocated_bytes":4096,"allocations":3}"##));
+        assert_eq!(from_json(&json).unwrap().memory, profiled.memory);
+        assert_eq!(to_csv(&profiled), "13,2,test,1,integer,12000,,1024,4096,3");
+        assert!(from_json("not json").is_err());
+    }
+
+    #[test]
+    fn csv_quotes_where_needed() {
+        assert_eq!(csv_header(), "day,part,input,answer,answer_type,elapsed_ns,error");
+        assert_eq!(to_csv(&result(Ok(Answer::Integer(17)))), "13,2,test,17,integer,12000,");
+        assert_eq!(to_csv(&result(Ok(Answer::Art(vec![String::from("#\"#"), String::from("# #")])))),
+                   "13,2,test,\"#\"\"#\n# #\",art,12000,");
+        assert_eq!(to_csv(&result(Err(String::from("expected `a,b`")))),
+                   "13,2,test,,,12000,\"expected `a,b`\"");
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+
+/// Describes why (and where) an input line couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// line within the input (starting at 1)
+    pub line: usize,
+    /// column within that line (starting at 1, counted in characters)
+    pub column: usize,
+    /// the text we choked on
+    pub text: String,
+    /// what we would have liked to see instead, e.g. `x1,y1 -> x2,y2`
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, expected: &str) -> Self {
+        Self {
+            line,
+            column,
+            text: String::from(text),
+            expected: String::from(expected),
+        }
+    }
+
+    /// The whole line `line_text` (at index `index` of the input) is wrong
+    pub fn at_line(index: usize, line_text: &str, expected: &str) -> Self {
+        Self::new(index + 1, 1, line_text, expected)
+    }
+
+    /// `part` (a sub slice of `line_text`) of the line at index `index` is wrong.
+    /// The column is derived from where `part` is located inside `line_text`
+    pub fn at_part(index: usize, line_text: &str, part: &str, expected: &str) -> Self {
+        Self::new(index + 1, column_of(line_text, part), part, expected)
+    }
+
+    /// The input ended (after `n_lines` lines) although we expected more
+    pub fn end_of_input(n_lines: usize, expected: &str) -> Self {
+        Self::new(n_lines + 1, 1, "", expected)
+    }
+
+    /// The same error for input that started `offset` lines later
+    /// (e.g. an error found in a section of the input)
+    pub fn shifted(self, offset: usize) -> Self {
+        Self { line: self.line + offset, ..self }
+    }
+}
+
+/// Finds the (1-based, character) column at which `part` starts inside `line`.
+/// `part` should be a slice of `line`; if it isn't we fall back to searching for it
+pub fn column_of(line: &str, part: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let part_start = part.as_ptr() as usize;
+
+    let is_sub_slice = part_start >= line_start && part_start + part.len() <= line_start + line.len();
+    let offset = if is_sub_slice {
+        part_start - line_start
+    } else {
+        line.find(part).unwrap_or(0)
+    };
+
+    line[..offset].chars().count() + 1
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "line {}, column {}: expected `{}`, found nothing",
+                   self.line, self.column, self.expected)
+        } else {
+            write!(f, "line {}, column {}: expected `{}`, found `{}`",
+                   self.line, self.column, self.expected, self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_a_sub_slice() {
+        let line = "0,9 -> 5,9";
+        assert_eq!(column_of(line, &line[7..]), 8);
+        assert_eq!(column_of(line, line), 1);
+    }
+
+    #[test]
+    fn column_counts_characters_not_bytes() {
+        let line = "äö x";
+        assert_eq!(column_of(line, &line[5..]), 4);
+    }
+
+    #[test]
+    fn column_of_a_copy_falls_back_to_searching() {
+        assert_eq!(column_of("forward 5", "5"), 9);
+    }
+
+    #[test]
+    fn display_mentions_position_and_expectation() {
+        let err = ParseError::at_part(2, "up x", "x", "<number>");
+        assert_eq!(err.to_string(), "line 3, column 4: expected `<number>`, found `x`");
+        let err = ParseError::end_of_input(3, "<board>");
+        assert_eq!(err.to_string(), "line 4, column 1: expected `<board>`, found nothing");
+    }
+}
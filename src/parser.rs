@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::str::FromStr;
+
+use crate::grid::Grid;
+use crate::parse_error::ParseError;
+
+/// A piece of one input line. It remembers the whole line and where that line is
+/// so every error can point at the exact line and column
+#[derive(Debug, Clone, Copy)]
+pub struct Span<'a> {
+    /// index of the line within the input
+    pub index: usize,
+    /// the whole line
+    pub line: &'a str,
+    /// the part we are looking at (a slice of `line`)
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// The whole line at `index`
+    pub fn new(index: usize, line: &'a str) -> Self {
+        Self { index, line, text: line }
+    }
+
+    /// A span for `text`, which has to be a slice of this span
+    fn sub(&self, text: &'a str) -> Self {
+        Self { index: self.index, line: self.line, text }
+    }
+
+    /// An error pointing at this span
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError::at_part(self.index, self.line, self.text, expected)
+    }
+}
+
+/// Anything that turns a [Span] into a `T`. Every function (or closure) with the
+/// right signature is a parser; the functions below build and combine them
+pub trait Parser<'a, T>: Fn(Span<'a>) -> Result<T, ParseError> {}
+
+impl<'a, T, F> Parser<'a, T> for F where F: Fn(Span<'a>) -> Result<T, ParseError> {}
+
+/// Runs `parser` on the line at `index`
+pub fn line<'a, T>(index: usize, line: &'a str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+    parser(Span::new(index, line))
+}
+
+/// A number (or anything else [FromStr] understands), surrounding whitespace is ignored
+pub fn integer<'a, T: FromStr>(expected: &'static str) -> impl Parser<'a, T> {
+    move |span: Span<'a>| {
+        let trimmed = span.sub(span.text.trim());
+        trimmed.text.parse::<T>().map_err(|_| trimmed.error(expected))
+    }
+}
+
+/// The text itself, it must not be empty
+pub fn word<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |span: Span<'a>| {
+        if span.text.is_empty() {
+            return Err(span.error(expected));
+        }
+        Ok(span.text)
+    }
+}
+
+/// One of the given keywords, turned into its value
+pub fn keyword<'a, T: Clone>(choices: &'static [(&'static str, T)]) -> impl Parser<'a, T> {
+    move |span: Span<'a>| {
+        choices
+            .iter()
+            .find(|(name, _)| *name == span.text)
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| span.error(&choices.iter().map(|(name, _)| *name).collect::<Vec<_>>().join("|")))
+    }
+}
+
+/// Every character turned into a value by `to_value`. The error points at the first
+/// character `to_value` doesn't like. Empty text is an error as well
+pub fn chars<'a, T>(expected: &'static str, to_value: impl Fn(char) -> Option<T>) -> impl Parser<'a, Vec<T>> {
+    move |span: Span<'a>| {
+        if span.text.is_empty() {
+            return Err(span.error(expected));
+        }
+        span.text
+            .char_indices()
+            .map(|(pos, c)| to_value(c).ok_or_else(|| span.sub(&span.text[pos..pos + c.len_utf8()]).error(expected)))
+            .collect()
+    }
+}
+
+/// The text has to start with `prefix`, `parser` gets the rest
+pub fn prefixed<'a, T>(prefix: &'static str, parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |span: Span<'a>| match span.text.strip_prefix(prefix) {
+        Some(rest) => parser(span.sub(rest)),
+        None => Err(span.error(prefix)),
+    }
+}
+
+/// Splits at the first `separator`; `expected` describes the whole thing (e.g. `x,y`)
+pub fn pair<'a, A, B>(separator: &'static str, expected: &'static str,
+                      left: impl Parser<'a, A>, right: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+    move |span: Span<'a>| match span.text.split_once(separator) {
+        Some((l, r)) => Ok((left(span.sub(l))?, right(span.sub(r))?)),
+        None => Err(span.error(expected)),
+    }
+}
+
+/// Splits at every `separator`. Empty items are handed to `item` as well
+pub fn list<'a, T>(separator: &'static str, item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |span: Span<'a>| span.text.split(separator).map(|part| item(span.sub(part))).collect()
+}
+
+/// Splits at every `separator`, skipping empty items (e.g. numbers aligned with several spaces)
+pub fn tokens<'a, T>(separator: char, item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |span: Span<'a>| span.text
+        .split(separator)
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| item(span.sub(part)))
+        .collect()
+}
+
+/// Splits at any amount of whitespace
+pub fn words<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |span: Span<'a>| span.text.split_whitespace().map(|part| item(span.sub(part))).collect()
+}
+
+/// The list produced by `parser` has to have exactly `count` items
+pub fn exactly<'a, T>(count: usize, expected: &'static str, parser: impl Parser<'a, Vec<T>>) -> impl Parser<'a, Vec<T>> {
+    move |span: Span<'a>| {
+        let items = parser(span)?;
+        if items.len() != count {
+            return Err(span.error(expected));
+        }
+        Ok(items)
+    }
+}
+
+/// Turns the result of `parser` into something else
+pub fn map<'a, A, B>(parser: impl Parser<'a, A>, f: impl Fn(A) -> B) -> impl Parser<'a, B> {
+    move |span: Span<'a>| parser(span).map(&f)
+}
+
+/// The result of `parser` has to pass `check`, otherwise the whole span is reported
+pub fn verify<'a, T>(parser: impl Parser<'a, T>, expected: &'static str, check: impl Fn(&T) -> bool) -> impl Parser<'a, T> {
+    move |span: Span<'a>| {
+        let value = parser(span)?;
+        if !check(&value) {
+            return Err(span.error(expected));
+        }
+        Ok(value)
+    }
+}
+
+/// A block of consecutive lines of the input (usually separated from the next one by a blank line)
+#[derive(Debug, Clone, Copy)]
+pub struct Section<'a> {
+    /// index of the first line within the input
+    pub offset: usize,
+    pub lines: &'a [String],
+}
+
+impl<'a> Section<'a> {
+    /// All of the input as one section
+    pub fn whole(lines: &'a [String]) -> Self {
+        Self { offset: 0, lines }
+    }
+
+    /// Runs `parser` on every line
+    pub fn each_line<T>(&self, parser: impl Parser<'a, T>) -> Result<Vec<T>, ParseError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| parser(Span::new(self.offset + i, line)))
+            .collect()
+    }
+
+    /// The section has to consist of exactly one line which is handed to `parser`
+    pub fn single_line<T>(&self, expected: &str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+        match self.lines {
+            [line] => parser(Span::new(self.offset, line)),
+            [] => Err(ParseError::end_of_input(self.offset, expected)),
+            [_, extra, ..] => Err(ParseError::at_line(self.offset + 1, extra, "a single line")),
+        }
+    }
+
+    /// Every line is one row of values; all rows have to be equally long
+    pub fn table<T>(&self, row: impl Parser<'a, Vec<T>>) -> Result<Grid<T>, ParseError> {
+        let rows = self.each_line(row)?;
+        if let Some(width) = rows.first().map(|first| first.len()) {
+            if let Some(i) = rows.iter().position(|row| row.len() != width) {
+                return Err(ParseError::at_line(self.offset + i, &self.lines[i], &format!("{} values", width)));
+            }
+        }
+        Grid::from_rows(rows).ok_or(ParseError::end_of_input(self.offset, "<row>"))
+    }
+
+    /// Every character is one cell of a grid (see [Grid::parse_with])
+    pub fn grid<T>(&self, expected: &str, to_value: impl Fn(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        Grid::parse_with(self.lines, expected, to_value).map_err(|err| err.shifted(self.offset))
+    }
+
+    /// Every line is a rule `key <separator> value`. A key may only get one rule,
+    /// a second one is reported at its key (instead of silently replacing the first)
+    pub fn rules<K: Eq + Hash, V>(&self, separator: &'static str, expected: &'static str,
+                                  key: impl Parser<'a, K>, value: impl Parser<'a, V>) -> Result<HashMap<K, V>, ParseError> {
+        let mut rules: HashMap<K, (usize, V)> = HashMap::with_capacity(self.lines.len());
+        let rule = pair(separator, expected, key, value);
+        for (i, line) in self.lines.iter().enumerate() {
+            let index = self.offset + i;
+            let (key, value) = rule(Span::new(index, line))?;
+            if let Some((first, _)) = rules.get(&key) {
+                let key_text = line.split(separator).next().unwrap_or(line).trim();
+                let expected = format!("a key without a rule yet (it got one in line {})", first + 1);
+                return Err(ParseError::at_part(index, line, key_text, &expected));
+            }
+            rules.insert(key, (index, value));
+        }
+        Ok(rules.into_iter().map(|(key, (_, value))| (key, value)).collect())
+    }
+}
+
+/// Splits the input at blank lines. Several blank lines in a row count as one separator
+pub fn sections(lines: &[String]) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        match (line.trim().is_empty(), start) {
+            (true, Some(offset)) => {
+                sections.push(Section { offset, lines: &lines[offset..i] });
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => {},
+        }
+    }
+    if let Some(offset) = start {
+        sections.push(Section { offset, lines: &lines[offset..] });
+    }
+
+    sections
+}
+
+/// Like [sections] but there have to be exactly as many sections as `expected`
+/// has descriptions (one per section, used for the error message if it is missing)
+pub fn exact_sections<'a>(lines: &'a [String], expected: &[&str]) -> Result<Vec<Section<'a>>, ParseError> {
+    let found = sections(lines);
+    if found.len() < expected.len() {
+        return Err(ParseError::end_of_input(lines.len(), expected[found.len()]));
+    }
+    if let Some(extra) = found.get(expected.len()) {
+        return Err(ParseError::at_line(extra.offset, &extra.lines[0], "end of input"));
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &[&str]) -> Vec<String> {
+        input.iter().map(|line| String::from(*line)).collect()
+    }
+
+    #[test]
+    fn integers_and_lists() {
+        assert_eq!(line(0, "3,4,3,1,2", list(",", integer::<u8>("<n>"))), Ok(vec![3, 4, 3, 1, 2]));
+        assert_eq!(line(0, " 8  2 23", tokens(' ', integer::<u8>("<n>"))), Ok(vec![8, 2, 23]));
+
+        let err = line(4, "3,4,x,1", list(",", integer::<u8>("<n>"))).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str(), err.expected.as_str()), (5, 5, "x", "<n>"));
+    }
+
+    #[test]
+    fn pairs_point_at_the_broken_half() {
+        let point = || pair(",", "x,y", integer::<i64>("<x>"), integer::<i64>("<y>"));
+        let segment = pair(" -> ", "x1,y1 -> x2,y2", point(), point());
+
+        assert_eq!(line(0, "0,9 -> -5,9", &segment), Ok(((0, 9), (-5, 9))));
+        let err = line(0, "0,9 -> 5;9", &segment).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (8, "x,y"));
+        let err = line(0, "0,9 => 5,9", &segment).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (1, "x1,y1 -> x2,y2"));
+    }
+
+    #[test]
+    fn keywords_prefixes_and_chars() {
+        const AXES: [(&str, char); 2] = [("x", 'x'), ("y", 'y')];
+        let fold = prefixed("fold along ", pair("=", "<x|y>=<n>", keyword(&AXES), integer::<u32>("<n>")));
+        assert_eq!(line(0, "fold along y=7", &fold), Ok(('y', 7)));
+        assert_eq!(line(0, "fold along z=7", &fold).unwrap_err().expected, "x|y");
+
+        let bits = chars("0|1", |c| c.to_digit(2));
+        assert_eq!(line(0, "0110", &bits), Ok(vec![0, 1, 1, 0]));
+        assert_eq!(line(0, "0120", &bits).unwrap_err().column, 3);
+        assert!(line(0, "", &bits).is_err());
+    }
+
+    #[test]
+    fn exactly_and_verify() {
+        let four = exactly(4, "4 numbers", words(integer::<u8>("<n>")));
+        assert!(line(0, "1 2 3 4", &four).is_ok());
+        assert_eq!(line(0, "1 2 3", &four).unwrap_err().expected, "4 numbers");
+
+        let small = verify(integer::<u8>("<0-8>"), "<0-8>", |n| *n <= 8);
+        assert_eq!(line(0, "9", &small).unwrap_err().expected, "<0-8>");
+    }
+
+    #[test]
+    fn splits_sections_at_blank_lines() {
+        let input = lines(&["NNCB", "", "", "CH -> B", "HH -> N", ""]);
+        let found = sections(&input);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[1].offset, found[1].lines.len()), (3, 2));
+
+        let rules = found[1].rules(" -> ", "AB -> C", word("AB"), word("C")).unwrap();
+        assert_eq!(rules.get("HH"), Some(&"N"));
+
+        let twice = lines(&["CH -> B", "HH -> N", "CH -> C"]);
+        let err = Section::whole(&twice).rules(" -> ", "AB -> C", word("AB"), word("C")).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, "CH"));
+        assert_eq!(err.expected, "a key without a rule yet (it got one in line 1)");
+
+        let err = exact_sections(&input, &["<template>", "<rules>", "<more>"]).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (7, "<more>"));
+        assert_eq!(exact_sections(&input, &["<template>"]).unwrap_err().line, 4);
+    }
+
+    #[test]
+    fn tables_report_ragged_rows_with_their_line() {
+        let input = lines(&["7,4", "", "1 2", "3 4 5"]);
+        let board = sections(&input)[1];
+        let err = board.table(words(integer::<u8>("<n>"))).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (4, "2 values"));
+
+        let err = board.grid("<digit>", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 2));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// The number of workers if nobody says otherwise: one per CPU
+pub fn default_workers() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Calls `f` for every item on a pool of `workers` threads (at least one).
+/// Every worker takes the next item nobody has taken yet, so a slow item doesn't hold up the others.
+/// The results come back in the order of `items`, no matter which finished first
+pub fn map_ordered<T: Sync, R: Send>(items: &[T], workers: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            let (sender, next, f) = (sender.clone(), &next, &f);
+            scope.spawn(move || {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    match items.get(index) {
+                        Some(item) => sender.send((index, f(item))).unwrap(),
+                        None => break,
+                    }
+                }
+            });
+        }
+    });
+    drop(sender);
+
+    let mut results: Vec<(usize, R)> = receiver.into_iter().collect();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Runs `f` on a thread of its own and waits at most `timeout` for its result.
+/// `None` if it took longer (or panicked). Threads can't be killed, so a runaway `f` keeps
+/// running in the background until the program ends; only its result is thrown away
+pub fn with_timeout<R: Send + 'static>(timeout: Duration, f: impl FnOnce() -> R + Send + 'static) -> Option<R> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // nobody listens anymore if we took too long
+        let _ = sender.send(f());
+    });
+    receiver.recv_timeout(timeout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn keeps_the_order_of_the_items() {
+        let finished = Mutex::new(Vec::new());
+        // the first item is the slowest, so it finishes last
+        let results = map_ordered(&[200, 0, 10, 20], 4, |millis| {
+            thread::sleep(Duration::from_millis(*millis));
+            finished.lock().unwrap().push(*millis);
+            millis * 2
+        });
+
+        assert_eq!(results, vec![400, 0, 20, 40]);
+        assert_eq!(finished.lock().unwrap().last(), Some(&200));
+        assert_eq!(map_ordered(&[1, 2, 3], 0, |n| n + 1), vec![2, 3, 4]);
+        assert!(map_ordered(&[] as &[u8], 3, |n| *n).is_empty());
+    }
+
+    #[test]
+    fn gives_up_on_slow_calls() {
+        let start = Instant::now();
+        assert_eq!(with_timeout(Duration::from_millis(20), || thread::sleep(Duration::from_secs(5))), None);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(with_timeout(Duration::from_secs(5), || 42), Some(42));
+    }
+}
@@ -0,0 +1,29 @@
+use crate::solution::AnySolution;
+
+/// All days we know about (sorted by day)
+static SOLUTIONS: &[&dyn AnySolution] = &[
+    &crate::day1::Day1,
+    &crate::day2::Day2,
+    &crate::day3::Day3,
+    &crate::day4::Day4,
+    &crate::day5::Day5,
+    &crate::day6::Day6,
+    &crate::day7::Day7,
+    &crate::day8::Day8,
+    &crate::day9::Day9,
+    &crate::day10::Day10,
+    &crate::day11::Day11,
+    &crate::day12::Day12,
+    &crate::day13::Day13,
+    &crate::day14::Day14,
+];
+
+/// Every registered solution
+pub fn all() -> &'static [&'static dyn AnySolution] {
+    SOLUTIONS
+}
+
+/// Looks up a single day
+pub fn find(day: u8) -> Option<&'static dyn AnySolution> {
+    SOLUTIONS.iter().find(|s| s.day() == day).copied()
+}
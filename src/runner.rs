@@ -0,0 +1,345 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::loader::{self, Input, LoadError};
+use crate::log::{info, warning};
+use crate::memory::{self, MemoryUsage};
+use crate::pool;
+use crate::solution::{AnySolution, Answer, Part};
+
+/// Which input file a day should be run on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputKind {
+    /// `src/dayXY/testinput.txt`
+    Test,
+    /// `src/dayXY/input.txt`
+    Real,
+    /// some arbitrary file
+    Path(PathBuf),
+    /// whatever is piped into the program
+    Stdin,
+}
+
+impl InputKind {
+    /// `test`, `real` and `-` (stdin) are keywords, everything else is taken as a path
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "test" => Self::Test,
+            "real" => Self::Real,
+            "-" => Self::Stdin,
+            path => Self::Path(PathBuf::from(path)),
+        }
+    }
+
+    /// Where the input for `day` lives (`None` for stdin)
+    pub fn path_for(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            Self::Test => Some(PathBuf::from(format!("./src/day{}/testinput.txt", day))),
+            Self::Real => Some(PathBuf::from(format!("./src/day{}/input.txt", day))),
+            Self::Path(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    /// Loads the input for `day`
+    pub fn load(&self, day: u8, trim: bool) -> Result<Input, LoadError> {
+        match self.path_for(day) {
+            Some(path) => loader::load_file(&path, trim),
+            None => loader::load_stdin(trim),
+        }
+    }
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Test => write!(f, "test"),
+            Self::Real => write!(f, "real"),
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// Outcome of running a single part of a single day
+#[derive(Debug)]
+pub struct RunResult {
+    pub day: u8,
+    pub part: Part,
+    pub input: InputKind,
+    pub outcome: Result<Answer, String>,
+    pub elapsed: Duration,
+    /// what parsing and solving allocated, if memory profiling is on (see `src/memory.rs`)
+    pub memory: Option<MemoryUsage>,
+}
+
+impl RunResult {
+    pub fn is_ok(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Turns whatever a panic carried into something printable
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        return msg.to_string();
+    }
+    if let Some(msg) = payload.downcast_ref::<String>() {
+        return msg.clone();
+    }
+    String::from("solution panicked")
+}
+
+/// Runs one part of one day on already loaded `data`.
+/// Panics inside the day are caught and reported as failures
+pub fn run_part(solution: &dyn AnySolution, part: Part, input: &InputKind, data: &Input) -> RunResult {
+    info!("Executing day {} part {} ({} input)", solution.day(), part, input);
+    let start = Instant::now();
+
+    let run = || panic::catch_unwind(AssertUnwindSafe(|| {
+        solution.run(data.lines(), part).map_err(|err| err.to_string())
+    })).unwrap_or_else(|payload| Err(panic_message(payload)));
+    let (outcome, memory) = if memory::is_enabled() {
+        let (outcome, usage) = memory::measure(run);
+        (outcome, Some(usage))
+    } else {
+        (run(), None)
+    };
+
+    RunResult {
+        day: solution.day(),
+        part,
+        input: input.clone(),
+        outcome,
+        elapsed: start.elapsed(),
+        memory,
+    }
+}
+
+/// Every part is reported as failed with `err` (e.g. because the input couldn't be loaded)
+fn all_failed(solution: &dyn AnySolution, parts: &[Part], input: &InputKind, err: &LoadError) -> Vec<RunResult> {
+    parts
+        .iter()
+        .map(|part| RunResult {
+            day: solution.day(),
+            part: *part,
+            input: input.clone(),
+            outcome: Err(err.to_string()),
+            elapsed: Duration::ZERO,
+            memory: None,
+        })
+        .collect()
+}
+
+/// Loads the input of a day once and runs all requested `parts` on it.
+/// If the input can't be loaded every part is reported as failed
+pub fn run_day(solution: &dyn AnySolution, parts: &[Part], input: &InputKind, trim: bool) -> Vec<RunResult> {
+    match input.load(solution.day(), trim) {
+        Ok(data) => parts
+            .iter()
+            .map(|part| run_part(solution, *part, input, &data))
+            .collect(),
+        Err(err) => all_failed(solution, parts, input, &err),
+    }
+}
+
+/// Runs all requested `parts` for every day in `days`
+pub fn run_days(days: &[&dyn AnySolution], parts: &[Part], input: &InputKind, trim: bool) -> Vec<RunResult> {
+    days.iter()
+        .flat_map(|day| run_day(*day, parts, input, trim))
+        .collect()
+}
+
+/// How `run_days_parallel` spreads the days over threads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// how many days run at the same time
+    pub workers: usize,
+    /// a part taking longer is reported as timed out (`None`: wait forever)
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { workers: pool::default_workers(), timeout: None }
+    }
+}
+
+/// Like [run_part], but gives up after `timeout`. The part is then reported as failed
+/// and keeps running in the background (it can't be stopped)
+pub fn run_part_within(solution: &'static dyn AnySolution, part: Part, input: &InputKind, data: &Arc<Input>, timeout: Duration) -> RunResult {
+    let (kind, shared) = (input.clone(), Arc::clone(data));
+    pool::with_timeout(timeout, move || run_part(solution, part, &kind, &shared))
+        .unwrap_or_else(|| {
+            warning!("Day {} part {} timed out after {:?}", solution.day(), part, timeout);
+            RunResult {
+                day: solution.day(),
+                part,
+                input: input.clone(),
+                outcome: Err(format!("timed out after {:?}", timeout)),
+                elapsed: timeout,
+                memory: None,
+            }
+        })
+}
+
+/// Runs all requested `parts` for every day in `days` on a pool of `config.workers` threads.
+/// The results are in the same order as with [run_days]
+pub fn run_days_parallel(days: &[&'static dyn AnySolution], parts: &[Part], input: &InputKind, trim: bool, config: &PoolConfig) -> Vec<RunResult> {
+    pool::map_ordered(days, config.workers, |solution| {
+        let data = match input.load(solution.day(), trim) {
+            Ok(data) => Arc::new(data),
+            Err(err) => return all_failed(*solution, parts, input, &err),
+        };
+        parts
+            .iter()
+            .map(|part| match config.timeout {
+                Some(timeout) => run_part_within(*solution, *part, input, &data, timeout),
+                None => run_part(*solution, *part, input, &data),
+            })
+            .collect::<Vec<RunResult>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Prints the results as a (plain text) table.
+/// Multi-line answers (ASCII art) continue on the following lines
+pub fn print_table(results: &[RunResult]) {
+    // memory columns only if it was profiled
+    let profiled = results.iter().any(|result| result.memory.is_some());
+    let memory_columns = |result: &RunResult| match &result.memory {
+        Some(usage) => format!(" | {:>10} | {:>10} | {:>8}",
+                               memory::format_bytes(usage.peak), memory::format_bytes(usage.total), usage.count),
+        None if profiled => format!(" | {:>10} | {:>10} | {:>8}", "", "", ""),
+        None => String::new(),
+    };
+
+    print!("{:>4} | {:>4} | {:<6} | {:<20} | {:>12}", "Day", "Part", "Input", "Answer", "Time");
+    if profiled {
+        print!(" | {:>10} | {:>10} | {:>8}", "Peak", "Allocated", "Allocs");
+    }
+    println!();
+    println!("{}", "-".repeat(if profiled { 97 } else { 58 }));
+    for result in results {
+        let answer = match &result.outcome {
+            Ok(answer) => answer.to_string(),
+            Err(msg) => format!("FAILED: {}", msg),
+        };
+        let mut rows = answer.lines();
+        println!("{:>4} | {:>4} | {:<6} | {:<20} | {:>12}{}",
+                 result.day,
+                 result.part,
+                 result.input.to_string(),
+                 rows.next().unwrap_or(""),
+                 format!("{:.3?}", result.elapsed),
+                 memory_columns(result));
+        for row in rows {
+            println!("{:>4} | {:>4} | {:<6} | {:<20} |", "", "", "", row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+
+    use super::*;
+    use crate::loader::Source;
+    use crate::parse_error::ParseError;
+    use crate::registry;
+    use crate::solution::{Solution, SolveError};
+
+    /// Part 1 answers right away, part 2 never finishes
+    struct Stuck;
+
+    impl Solution for Stuck {
+        type Input = u64;
+
+        fn day(&self) -> u8 {
+            99
+        }
+
+        fn title(&self) -> &'static str {
+            "Stuck"
+        }
+
+        fn parse(&self, lines: &[String]) -> Result<u64, ParseError> {
+            Ok(lines.len() as u64)
+        }
+
+        fn part1(&self, input: &u64) -> Result<Answer, SolveError> {
+            Ok(Answer::Integer(*input))
+        }
+
+        fn part2(&self, _input: &u64) -> Result<Answer, SolveError> {
+            loop {
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+
+    static STUCK: Stuck = Stuck;
+
+    /// Part 1 fails with an error, part 2 panics
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = ();
+
+        fn day(&self) -> u8 {
+            98
+        }
+
+        fn title(&self) -> &'static str {
+            "Broken"
+        }
+
+        fn parse(&self, _lines: &[String]) -> Result<(), ParseError> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &()) -> Result<Answer, SolveError> {
+            Err(SolveError::new("out of range"))
+        }
+
+        fn part2(&self, _input: &()) -> Result<Answer, SolveError> {
+            panic!("gave up")
+        }
+    }
+
+    #[test]
+    fn a_failing_part_is_reported_like_a_panic() {
+        let data = Input { source: Source::Stdin, lines: vec![String::from("1")] };
+        let outcome = |part| run_part(&Broken, part, &InputKind::Stdin, &data).outcome;
+        assert_eq!(outcome(Part::One), Err(String::from("out of range")));
+        assert_eq!(outcome(Part::Two), Err(String::from("gave up")));
+    }
+
+    #[test]
+    fn a_runaway_part_times_out_and_the_rest_still_runs() {
+        let path = std::env::temp_dir().join(format!("aoc_runner_{}", std::process::id()));
+        fs::write(&path, "199\n200\n208\n").unwrap();
+
+        let days = [&STUCK as &dyn AnySolution, registry::find(1).unwrap()];
+        let config = PoolConfig { workers: 2, timeout: Some(Duration::from_millis(100)) };
+        let results = run_days_parallel(&days, &Part::BOTH, &InputKind::Path(path.clone()), true, &config);
+
+        let summary: Vec<(u8, Part, Result<Answer, String>)> = results
+            .into_iter()
+            .map(|result| (result.day, result.part, result.outcome))
+            .collect();
+        assert_eq!(summary, vec![
+            (99, Part::One, Ok(Answer::Integer(3))),
+            (99, Part::Two, Err(String::from("timed out after 100ms"))),
+            (1, Part::One, Ok(Answer::Integer(2))),
+            (1, Part::Two, Ok(Answer::Integer(0))),
+        ]);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The skeleton of a new day. `{day}` and `{title}` are replaced
+const MOD_TEMPLATE: &str = r#"use crate::parse_error::ParseError;
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Vec<String>;
+
+    fn day(&self) -> u8 {
+        {day}
+    }
+
+    fn title(&self) -> &'static str {
+        "{title}"
+    }
+
+    /// TODO: turn the lines into something the parts can work with
+    fn parse(&self, lines: &[String]) -> Result<Vec<String>, ParseError> {
+        Ok(lines.to_vec())
+    }
+
+    fn part1(&self, _input: &Vec<String>) -> Result<Answer, SolveError> {
+        Err(SolveError::new("day {day} part 1 is not implemented yet"))
+    }
+
+    fn part2(&self, _input: &Vec<String>) -> Result<Answer, SolveError> {
+        Err(SolveError::new("day {day} part 2 is not implemented yet"))
+    }
+}
+"#;
+
+/// What `answers.txt` starts with, see [crate::expected::ExpectedAnswers]
+const ANSWERS_TEMPLATE: &str = "# <test|real> <part> = <expected answer>\n";
+
+/// The test stub appended to `tests/examples.rs`. `{day}` and `{name}` are replaced
+const TEST_TEMPLATE: &str = r#"
+#[test]
+#[ignore = "day {day} is not solved yet"]
+fn day{day}_{name}() {
+    // TODO: put the example answers from task.txt here
+    assert_example({day}, 0, 0);
+}
+"#;
+
+/// `Passage Pathing` -> `passage_pathing`
+fn to_snake_case(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+/// The number of the day in a line like `pub mod day12;` or `    &crate::day12::Day12,`
+fn day_in_line(line: &str, prefix: &str) -> Option<u8> {
+    let rest = line.trim_start().strip_prefix(prefix)?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Inserts `new_line` into `content` right after the last line (starting with `prefix`)
+/// which refers to a smaller day, so the days stay sorted.
+/// If there is no such line it goes in front of the first one with a bigger day
+fn insert_sorted(content: &str, prefix: &str, day: u8, new_line: &str) -> Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let days: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| day_in_line(line, prefix).map(|d| (i, d)))
+        .collect();
+
+    if days.iter().any(|(_, d)| *d == day) {
+        return Err(format!("Day {} is already registered", day));
+    }
+
+    let index = match days.iter().rfind(|(_, d)| *d < day) {
+        Some((i, _)) => i + 1,
+        None => days.first().map(|(i, _)| *i).ok_or(format!("Found no line starting with {}", prefix))?,
+    };
+    lines.insert(index, String::from(new_line));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Creates `src/day{day}/` below `root` with `mod.rs`, `task.txt`, `testinput.txt`, `input.txt` and `answers.txt`,
+/// registers the day in `src/lib.rs` and `src/registry.rs` and adds an (ignored) test
+/// to `tests/examples.rs`. Returns all files that were created or changed
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {} in the advent calendar", day));
+    }
+
+    let dir = root.join(format!("src/day{}", day));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let tests_path = root.join("tests/examples.rs");
+    let read = |path: &Path| fs::read_to_string(path)
+        .map_err(|err| format!("Couldn't read {}: {}", path.display(), err));
+
+    // compute every change first so we don't leave a half registered day behind
+    let lib = insert_sorted(&read(&lib_path)?, "pub mod day", day, &format!("pub mod day{};", day))?;
+    let registry = insert_sorted(&read(&registry_path)?, "&crate::day", day,
+                                 &format!("    &crate::day{0}::Day{0},", day))?;
+    let tests = read(&tests_path)? + &TEST_TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{name}", &to_snake_case(title));
+    let module = MOD_TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title.replace('"', "\\\""));
+
+    let files = [
+        (dir.join("mod.rs"), module),
+        (dir.join("task.txt"), String::new()),
+        (dir.join("testinput.txt"), String::new()),
+        (dir.join("input.txt"), String::new()),
+        (dir.join("answers.txt"), String::from(ANSWERS_TEMPLATE)),
+        (lib_path, lib),
+        (registry_path, registry),
+        (tests_path, tests),
+    ];
+
+    fs::create_dir_all(&dir).map_err(|err| format!("Couldn't create {}: {}", dir.display(), err))?;
+    for (path, content) in &files {
+        fs::write(path, content).map_err(|err| format!("Couldn't write {}: {}", path.display(), err))?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expected::ExpectedAnswers;
+
+    #[test]
+    fn title_to_snake_case() {
+        assert_eq!(to_snake_case("Dive!"), "dive");
+        assert_eq!(to_snake_case("The Treachery of Whales"), "the_treachery_of_whales");
+    }
+
+    #[test]
+    fn inserts_between_days() {
+        let content = "pub mod day1;\npub mod day10;\npub mod day2;\npub mod cli;\n";
+        assert_eq!(insert_sorted(content, "pub mod day", 3, "pub mod day3;").unwrap(),
+                   "pub mod day1;\npub mod day10;\npub mod day2;\npub mod day3;\npub mod cli;\n");
+    }
+
+    #[test]
+    fn refuses_registered_days() {
+        assert!(insert_sorted("    &crate::day4::Day4,\n", "&crate::day", 4, "").is_err());
+    }
+
+    #[test]
+    fn creates_and_registers_a_day() {
+        let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("tests")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day1;\npub mod day14;\npub mod cli;\n").unwrap();
+        fs::write(root.join("src/registry.rs"), "static S: &[&dyn A] = &[\n    &crate::day1::Day1,\n    &crate::day14::Day14,\n];\n").unwrap();
+        fs::write(root.join("tests/examples.rs"), "// examples\n").unwrap();
+
+        let files = new_day(&root, 15, "Chiton").unwrap();
+        assert_eq!(files.len(), 8);
+        assert!(root.join("src/day15/testinput.txt").exists());
+        assert_eq!(fs::read_to_string(root.join("src/day15/answers.txt")).unwrap(), ANSWERS_TEMPLATE);
+        assert_eq!(ExpectedAnswers::parse(ANSWERS_TEMPLATE), Ok(ExpectedAnswers::default()));
+        let module = fs::read_to_string(root.join("src/day15/mod.rs")).unwrap();
+        assert!(module.contains("pub struct Day15;"));
+        assert!(module.contains("Err(SolveError::new(\"day 15 part 2 is not implemented yet\"))"));
+        assert!(fs::read_to_string(root.join("src/lib.rs")).unwrap().contains("pub mod day14;\npub mod day15;\npub mod cli;"));
+        assert!(fs::read_to_string(root.join("src/registry.rs")).unwrap().contains("Day14,\n    &crate::day15::Day15,\n];"));
+        assert!(fs::read_to_string(root.join("tests/examples.rs")).unwrap().contains("fn day15_chiton()"));
+
+        // the day exists now
+        assert!(new_day(&root, 15, "Chiton").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,196 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::parse_error::ParseError;
+
+/// The two parts every day consists of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    /// `1` and `2` are the only valid part numbers
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(Self::One),
+            2 => Some(Self::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    /// delegates to the number so width and alignment (`{:>4}`) work in tables
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.number(), f)
+    }
+}
+
+/// Whatever a part computes.
+/// Most days produce a number, some produce a string and day 13 draws letters (ASCII art)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(u64),
+    Text(String),
+    /// one entry per row of the drawing
+    Art(Vec<String>),
+}
+
+impl Answer {
+    /// Short name of the variant (`integer`, `text`, `art`)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::Text(_) => "text",
+            Self::Art(_) => "art",
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Text(value) => write!(f, "{}", value),
+            Self::Art(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+/// Why a part couldn't come up with an answer although its input parsed fine
+/// (e.g. the input takes the computation out of range). The runner reports it like a panic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveError {
+    pub message: String,
+}
+
+impl SolveError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Why [AnySolution::run] didn't produce an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    Parse(ParseError),
+    Solve(SolveError),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{}", err),
+            Self::Solve(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+impl From<ParseError> for RunError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<SolveError> for RunError {
+    fn from(err: SolveError) -> Self {
+        Self::Solve(err)
+    }
+}
+
+/// Common interface of all days.
+/// The input lines are parsed once into [Solution::Input] which both parts work on
+pub trait Solution {
+    type Input;
+
+    /// Number of the day (1 - 25)
+    fn day(&self) -> u8;
+
+    /// The riddles' title
+    fn title(&self) -> &'static str;
+
+    /// Turns the raw input lines into something the parts can work with
+    fn parse(&self, lines: &[String]) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer, SolveError>;
+}
+
+/// Type erased version of [Solution] so we can keep all days in one list.
+/// Every [Solution] implements it automatically
+pub trait AnySolution: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    /// parses the input into an opaque value which can be handed to [AnySolution::solve]
+    fn parse_any(&self, lines: &[String]) -> Result<Box<dyn Any>, ParseError>;
+
+    /// runs `part` on a value produced by [AnySolution::parse_any]
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer, SolveError>;
+
+    /// Convenience: parse and solve in one go
+    fn run(&self, lines: &[String], part: Part) -> Result<Answer, RunError> {
+        let input = self.parse_any(lines)?;
+        Ok(self.solve(input.as_ref(), part)?)
+    }
+}
+
+impl<S> AnySolution for S where S: Solution + Sync, S::Input: 'static {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Solution::title(self)
+    }
+
+    fn parse_any(&self, lines: &[String]) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(self.parse(lines)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer, SolveError> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("Input was not produced by this solution");
+
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::solution::SolveError;
+
+/// A position `(x, y)` on an unbounded plane. `y` grows downwards, like on screen
+pub type Point = (i64, i64);
+
+/// Moving `point` would leave the range of `i64` (e.g. mirroring it at a far away axis)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub point: Point,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}) would move out of the range of i64", self.point.0, self.point.1)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+impl From<OutOfRange> for SolveError {
+    fn from(err: OutOfRange) -> Self {
+        SolveError::new(err.to_string())
+    }
+}
+
+/// `2 * axis - value`, computed wide enough to not overflow on the way
+fn mirror(axis: i64, value: i64) -> Option<i64> {
+    i64::try_from(2 * axis as i128 - value as i128).ok()
+}
+
+/// The smallest rectangle containing a set of points (both corners inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl Bounds {
+    /// A rectangle covering just `point`
+    pub fn around(point: Point) -> Self {
+        Self { min_x: point.0, min_y: point.1, max_x: point.0, max_y: point.1 }
+    }
+
+    /// The bounds of all `points`, `None` if there are none
+    pub fn of<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds: Option<Self>, point| match bounds {
+            Some(bounds) => Some(bounds.extended(*point)),
+            None => Some(Self::around(*point)),
+        })
+    }
+
+    /// The bounds grown just enough to also contain `point`
+    pub fn extended(&self, point: Point) -> Self {
+        Self {
+            min_x: self.min_x.min(point.0),
+            min_y: self.min_y.min(point.1),
+            max_x: self.max_x.max(point.0),
+            max_y: self.max_y.max(point.1),
+        }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min_x..=self.max_x).contains(&point.0) && (self.min_y..=self.max_y).contains(&point.1)
+    }
+
+    /// Is `point` on the border of the rectangle?
+    fn touches(&self, point: Point) -> bool {
+        point.0 == self.min_x || point.0 == self.max_x || point.1 == self.min_y || point.1 == self.max_y
+    }
+
+    pub fn width(&self) -> u64 {
+        self.max_x.abs_diff(self.min_x) + 1
+    }
+
+    pub fn height(&self) -> u64 {
+        self.max_y.abs_diff(self.min_y) + 1
+    }
+}
+
+/// Values at arbitrary (also negative or huge) positions. Only positions which were set use memory.
+/// The bounding box is kept up to date while inserting and only recomputed
+/// (lazily) if a point on its border is removed
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Cell<Option<Bounds>>,
+    /// the cached bounds have to be recomputed
+    stale: Cell<bool>,
+}
+
+/// A set of points, e.g. marked positions on a sheet
+pub type PointSet = SparseGrid<()>;
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: Cell::new(None),
+            stale: Cell::new(false),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn grow_bounds(&mut self, point: Point) {
+        if !self.stale.get() {
+            let bounds = match self.bounds.get() {
+                Some(bounds) => bounds.extended(point),
+                None => Bounds::around(point),
+            };
+            self.bounds.set(Some(bounds));
+        }
+    }
+
+    /// Sets the value at `point`, returns the previous one
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.grow_bounds(point);
+        self.cells.insert(point, value)
+    }
+
+    /// The value at `point`; if there is none it is set to `default` first
+    pub fn get_or_insert(&mut self, point: Point, default: T) -> &mut T {
+        self.grow_bounds(point);
+        self.cells.entry(point).or_insert(default)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let removed = self.cells.remove(&point);
+        if removed.is_some() && self.bounds.get().is_some_and(|bounds| bounds.touches(point)) {
+            self.stale.set(true);
+        }
+        removed
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The smallest rectangle containing all points, `None` if the grid is empty
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.stale.get() {
+            self.bounds.set(Bounds::of(self.cells.keys()));
+            self.stale.set(false);
+        }
+        self.bounds.get()
+    }
+
+    /// All points with their values (in no particular order)
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// Moves every point to `f(point)`. If two points end up on the same spot
+    /// their values are combined with `merge(existing, moved)`
+    pub fn transform(self, f: impl Fn(Point) -> Point, merge: impl Fn(T, T) -> T) -> Self {
+        match self.try_transform(|point| Some(f(point)), merge) {
+            Ok(moved) => moved,
+            Err(_) => unreachable!("f moves every point"),
+        }
+    }
+
+    /// Like [SparseGrid::transform], but `f` may refuse to move a point (`None`), which fails the whole move
+    pub fn try_transform(self, f: impl Fn(Point) -> Option<Point>, merge: impl Fn(T, T) -> T) -> Result<Self, OutOfRange> {
+        let mut moved: HashMap<Point, T> = HashMap::with_capacity(self.cells.len());
+        for (point, value) in self.cells {
+            let target = f(point).ok_or(OutOfRange { point })?;
+            let value = match moved.remove(&target) {
+                Some(existing) => merge(existing, value),
+                None => value,
+            };
+            moved.insert(target, value);
+        }
+
+        Ok(Self {
+            bounds: Cell::new(Bounds::of(moved.keys())),
+            stale: Cell::new(false),
+            cells: moved,
+        })
+    }
+
+    /// Shifts every point by `(dx, dy)`
+    pub fn translate(self, dx: i64, dy: i64) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| Some((x.checked_add(dx)?, y.checked_add(dy)?)), |_, moved| moved)
+    }
+
+    /// Mirrors every point at the vertical line at `x = axis`
+    pub fn reflect_x(self, axis: i64, merge: impl Fn(T, T) -> T) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| Some((mirror(axis, x)?, y)), merge)
+    }
+
+    /// Mirrors every point at the horizontal line at `y = axis`
+    pub fn reflect_y(self, axis: i64, merge: impl Fn(T, T) -> T) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| Some((x, mirror(axis, y)?)), merge)
+    }
+
+    /// Rotates by 90 degrees clockwise (as seen on screen) around the origin
+    pub fn rotate_clockwise(self) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| Some((y.checked_neg()?, x)), |_, moved| moved)
+    }
+
+    /// Rotates by 90 degrees counter clockwise (as seen on screen) around the origin
+    pub fn rotate_counter_clockwise(self) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| Some((y, x.checked_neg()?)), |_, moved| moved)
+    }
+
+    /// Draws the bounding box row by row, `to_char` gets the value at each position (if any)
+    pub fn render(&self, to_char: impl Fn(Option<&T>) -> char) -> Vec<String> {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Vec::new(),
+        };
+
+        (bounds.min_y..=bounds.max_y)
+            .map(|y| (bounds.min_x..=bounds.max_x).map(|x| to_char(self.get((x, y)))).collect())
+            .collect()
+    }
+}
+
+impl PointSet {
+    /// Marks `point`, returns whether it was new
+    pub fn mark(&mut self, point: Point) -> bool {
+        self.insert(point, ()).is_none()
+    }
+
+    /// Mirrors only the points right of `x = axis` onto the left side (like folding paper)
+    pub fn fold_left(self, axis: i64) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| if x > axis { Some((mirror(axis, x)?, y)) } else { Some((x, y)) }, |_, _| ())
+    }
+
+    /// Mirrors only the points below `y = axis` onto the upper side (like folding paper)
+    pub fn fold_up(self, axis: i64) -> Result<Self, OutOfRange> {
+        self.try_transform(|(x, y)| if y > axis { Some((x, mirror(axis, y)?)) } else { Some((x, y)) }, |_, _| ())
+    }
+
+    /// Marked points are drawn as `#`, everything else as ` `
+    pub fn render_marks(&self) -> Vec<String> {
+        self.render(|mark| if mark.is_some() { '#' } else { ' ' })
+    }
+}
+
+impl FromIterator<Point> for PointSet {
+    fn from_iter<I: IntoIterator<Item = Point>>(points: I) -> Self {
+        let mut set = Self::new();
+        for point in points {
+            set.mark(point);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_grow_and_shrink() {
+        let mut set: PointSet = [(0, 0), (-5, 3), (2, -7)].into_iter().collect();
+        assert_eq!(set.bounds(), Some(Bounds { min_x: -5, min_y: -7, max_x: 2, max_y: 3 }));
+
+        set.remove((-5, 3));
+        assert_eq!(set.bounds(), Some(Bounds { min_x: 0, min_y: -7, max_x: 2, max_y: 0 }));
+
+        set.mark((1_000_000_000_000, 0));
+        assert_eq!(set.bounds().unwrap().width(), 1_000_000_000_001);
+
+        set.remove((0, 0));
+        set.remove((2, -7));
+        set.remove((1_000_000_000_000, 0));
+        assert_eq!(set.bounds(), None);
+    }
+
+    #[test]
+    fn counts_per_point() {
+        let mut grid = SparseGrid::new();
+        for point in [(1, 1), (-1, 1), (1, 1)] {
+            *grid.get_or_insert(point, 0) += 1;
+        }
+        assert_eq!(grid.get((1, 1)), Some(&2));
+        assert_eq!(grid.get((-1, 1)), Some(&1));
+        assert_eq!(grid.len(), 2);
+    }
+
+    #[test]
+    fn transforms() {
+        let set: PointSet = [(1, 0), (2, 1)].into_iter().collect();
+        let expected = |points: &[Point]| points.iter().copied().collect::<PointSet>();
+
+        assert_eq!(set.clone().translate(-1, 2), Ok(expected(&[(0, 2), (1, 3)])));
+        assert_eq!(set.clone().reflect_x(0, |_, _| ()), Ok(expected(&[(-1, 0), (-2, 1)])));
+        assert_eq!(set.clone().reflect_y(1, |_, _| ()), Ok(expected(&[(1, 2), (2, 1)])));
+        assert_eq!(set.clone().rotate_clockwise(), Ok(expected(&[(0, 1), (-1, 2)])));
+        assert_eq!(set.clone().rotate_clockwise().unwrap().rotate_counter_clockwise(), Ok(set));
+    }
+
+    #[test]
+    fn merges_colliding_points() {
+        let mut grid = SparseGrid::new();
+        grid.insert((0, 0), 1);
+        grid.insert((2, 0), 2);
+        let folded = grid.reflect_x(1, |a, b| a + b).unwrap();
+        assert_eq!(folded.get((2, 0)), Some(&1));
+        assert_eq!(folded.get((0, 0)), Some(&2));
+
+        let set: PointSet = [(0, 0), (0, 4), (0, 3)].into_iter().collect();
+        assert_eq!(set.fold_up(2), Ok([(0, 0), (0, 1)].into_iter().collect()));
+    }
+
+    #[test]
+    fn reports_points_leaving_the_plane() {
+        let far: PointSet = [(i64::MAX, 0), (0, i64::MIN)].into_iter().collect();
+        // the mirror image is in range although 2 * axis isn't
+        let folded = far.clone().fold_left(i64::MAX - 1).unwrap().fold_up(i64::MIN / 2);
+        assert_eq!(folded, Ok([(i64::MAX - 2, i64::MIN), (0, i64::MIN)].into_iter().collect()));
+
+        assert_eq!(far.clone().reflect_x(-1, |_, _| ()), Err(OutOfRange { point: (i64::MAX, 0) }));
+        assert_eq!(far.clone().fold_left(-1), Err(OutOfRange { point: (i64::MAX, 0) }));
+        assert_eq!(far.clone().reflect_y(1, |_, _| ()), Err(OutOfRange { point: (0, i64::MIN) }));
+        assert_eq!(far.clone().rotate_clockwise(), Err(OutOfRange { point: (0, i64::MIN) }));
+        assert!(far.translate(1, 0).is_err());
+    }
+
+    #[test]
+    fn renders_the_bounding_box() {
+        let set: PointSet = [(-1, -1), (1, 0)].into_iter().collect();
+        assert_eq!(set.render_marks(), vec!["#  ", "  #"]);
+        assert!(PointSet::new().render_marks().is_empty());
+    }
+}
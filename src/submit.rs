@@ -0,0 +1,323 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::fetch::{RateLimiter, STAMP_FILE};
+use crate::html;
+use crate::http;
+use crate::log::{info, warning};
+use crate::parse_error::ParseError;
+use crate::solution::Part;
+
+/// How the website judged a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without a hint
+    Wrong,
+    /// we have to wait before we may guess again; the answer wasn't checked
+    RateLimited,
+    /// the part was solved before; the answer wasn't checked
+    AlreadySolved,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 6] = [Self::Correct, Self::TooHigh, Self::TooLow, Self::Wrong, Self::RateLimited, Self::AlreadySolved];
+
+    /// Name used in the history file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too_high",
+            Self::TooLow => "too_low",
+            Self::Wrong => "wrong",
+            Self::RateLimited => "rate_limited",
+            Self::AlreadySolved => "already_solved",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|outcome| outcome.name() == name)
+    }
+
+    /// Was the answer checked and found to be wrong?
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Wrong)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Figures out the outcome from the page the website answers with. Also returns the page's text
+pub fn parse_response(html_page: &str) -> Result<(Outcome, String), String> {
+    let text = String::from(html::to_text(html_page).trim());
+
+    let outcome = if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("too high") {
+        Outcome::TooHigh
+    } else if text.contains("too low") {
+        Outcome::TooLow
+    } else if text.contains("not the right answer") {
+        Outcome::Wrong
+    } else if text.contains("answer too recently") {
+        Outcome::RateLimited
+    } else if text.contains("Did you already complete it") {
+        Outcome::AlreadySolved
+    } else {
+        return Err(format!("Don't understand the website's answer: {}", text));
+    };
+
+    Ok((outcome, text))
+}
+
+/// One submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub part: Part,
+    pub outcome: Outcome,
+    pub answer: String,
+}
+
+/// Should an answer be submitted, given the history?
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Fine,
+    /// it may be submitted, but the history suggests it's wrong
+    Suspicious(String),
+    /// submitting it makes no sense
+    Refused(String),
+}
+
+/// Every answer submitted for a day, stored in `src/dayXY/submissions.txt`.
+/// Every attempt is one line `<1|2> <outcome> = <answer>`, oldest first.
+/// Lines starting with `#` are comments
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// Where the history of `day` lives below `root`
+    pub fn path_for(root: &Path, day: u8) -> PathBuf {
+        root.join(format!("src/day{}/submissions.txt", day))
+    }
+
+    /// Loads the history at `path`. A missing file just means nothing was submitted yet
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|err| format!("{}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Couldn't read {}: {}", path.display(), err)),
+        }
+    }
+
+    /// Parses the content of a history file (see [History])
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut attempts = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, answer) = line.split_once('=').ok_or(ParseError::at_line(i, line, "<1|2> <outcome> = <answer>"))?;
+            let mut key_parts = key.split_whitespace();
+            let part_str = key_parts.next().unwrap_or(key);
+            let part = part_str
+                .parse::<u8>()
+                .ok()
+                .and_then(Part::from_number)
+                .ok_or(ParseError::at_part(i, line, part_str, "1|2"))?;
+            let outcome_str = key_parts.next().unwrap_or(key);
+            let outcome = Outcome::from_name(outcome_str)
+                .ok_or(ParseError::at_part(i, line, outcome_str, "<outcome>"))?;
+
+            attempts.push(Attempt { part, outcome, answer: String::from(answer.trim()) });
+        }
+
+        Ok(Self { attempts })
+    }
+
+    /// Adds `attempt` to the history at `path` (creating the file if needed)
+    pub fn append(path: &Path, attempt: &Attempt) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "# <part> <outcome> = <submitted answer>")?;
+        }
+        writeln!(file, "{} {} = {}", attempt.part, attempt.outcome, attempt.answer)
+    }
+
+    fn attempts_of(&self, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts.iter().filter(move |attempt| attempt.part == part)
+    }
+
+    /// The range a (numeric) answer has to lie in, according to the `too_high` and `too_low` hints:
+    /// (biggest answer which was too low, smallest answer which was too high)
+    pub fn bounds(&self, part: Part) -> (Option<i128>, Option<i128>) {
+        let numbers = |outcome: Outcome| self
+            .attempts_of(part)
+            .filter(move |attempt| attempt.outcome == outcome)
+            .filter_map(|attempt| attempt.answer.parse::<i128>().ok());
+
+        (numbers(Outcome::TooLow).max(), numbers(Outcome::TooHigh).min())
+    }
+
+    /// Checks `answer` against what we already know about `part`
+    pub fn check(&self, part: Part, answer: &str) -> Check {
+        if let Some(correct) = self.attempts_of(part).find(|attempt| attempt.outcome == Outcome::Correct) {
+            return Check::Refused(format!("Part {} was already solved with {}", part, correct.answer));
+        }
+        if let Some(wrong) = self.attempts_of(part).find(|attempt| attempt.outcome.is_wrong() && attempt.answer == answer) {
+            return Check::Refused(format!("{} was already submitted for part {} and was {}", answer, part, wrong.outcome));
+        }
+
+        if let Ok(number) = answer.parse::<i128>() {
+            match self.bounds(part) {
+                (Some(low), _) if number <= low =>
+                    return Check::Suspicious(format!("{} is not bigger than {} which was too low", answer, low)),
+                (_, Some(high)) if number >= high =>
+                    return Check::Suspicious(format!("{} is not smaller than {} which was too high", answer, high)),
+                _ => {},
+            }
+        }
+
+        Check::Fine
+    }
+}
+
+/// What happened to a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub outcome: Outcome,
+    /// the website's explanation
+    pub message: String,
+}
+
+/// Posts `answer` for `part` of `day` unless the history (below `root`) says it's pointless.
+/// Every answer that was actually posted is added to the history
+pub fn submit(config: &Config, root: &Path, day: u8, part: Part, answer: &str) -> Result<Submission, String> {
+    let path = History::path_for(root, day);
+    let history = History::load(&path)?;
+
+    match history.check(part, answer) {
+        Check::Refused(reason) => return Err(format!("Not submitting: {}", reason)),
+        Check::Suspicious(reason) => warning!("{}", reason),
+        Check::Fine => {},
+    }
+
+    let session = config.session.as_deref().ok_or("No session token configured (set `session = ...` in the config file)")?;
+    let url = config.answer_url(day);
+    let level = part.to_string();
+
+    RateLimiter::new(config.min_interval, Some(root.join(STAMP_FILE))).wait();
+    info!("Submitting {} for day {} part {} to {}", answer, day, part, url);
+    let response = http::post_form(&url, Some(session), &[("level", &level), ("answer", answer)])?;
+    if !response.is_success() {
+        return Err(format!("{} answered with status {}", url, response.status));
+    }
+
+    let (outcome, message) = parse_response(&response.body)?;
+    let attempt = Attempt { part, outcome, answer: String::from(answer) };
+    fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| History::append(&path, &attempt))
+        .map_err(|err| format!("Couldn't record the attempt in {}: {}", path.display(), err))?;
+
+    Ok(Submission { outcome, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::http::Response;
+    use crate::mock_server::MockServer;
+
+    fn page(text: &str) -> String {
+        format!("<html><main><article><p>{}</p></article></main></html>", text)
+    }
+
+    #[test]
+    fn understands_the_website() {
+        let outcome = |text: &str| parse_response(&page(text)).map(|(outcome, _)| outcome);
+        assert_eq!(outcome("That's the right answer! You are one gold star closer."), Ok(Outcome::Correct));
+        assert_eq!(outcome("That's not the right answer; your answer is too high."), Ok(Outcome::TooHigh));
+        assert_eq!(outcome("That's not the right answer; your answer is too low."), Ok(Outcome::TooLow));
+        assert_eq!(outcome("That's not the right answer. If you're stuck, ..."), Ok(Outcome::Wrong));
+        assert_eq!(outcome("You gave an answer too recently; you have 43s left to wait."), Ok(Outcome::RateLimited));
+        assert_eq!(outcome("You don't seem to be solving the right level.  Did you already complete it?"), Ok(Outcome::AlreadySolved));
+        assert!(outcome("Something else").is_err());
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let content = "# comment\n1 too_high = 900\n1 too_low = 100\n1 wrong = 555\n2 rate_limited = 7\n";
+        let history = History::parse(content).unwrap();
+        assert_eq!(history.attempts.len(), 4);
+        assert_eq!(history.attempts[1], Attempt { part: Part::One, outcome: Outcome::TooLow, answer: String::from("100") });
+        assert_eq!(History::parse("3 wrong = 1").unwrap_err().column, 1);
+        assert_eq!(History::parse("1 meh = 1").unwrap_err().text, "meh");
+    }
+
+    #[test]
+    fn checks_answers_against_the_history() {
+        let history = History::parse("1 too_high = 900\n1 too_low = 100\n1 too_low = 300\n1 wrong = 555\n2 rate_limited = 7\n").unwrap();
+
+        assert_eq!(history.bounds(Part::One), (Some(300), Some(900)));
+        assert_eq!(history.check(Part::One, "400"), Check::Fine);
+        assert!(matches!(history.check(Part::One, "555"), Check::Refused(_)));
+        assert!(matches!(history.check(Part::One, "900"), Check::Refused(_)));
+        assert!(matches!(history.check(Part::One, "250"), Check::Suspicious(_)));
+        assert!(matches!(history.check(Part::One, "1000"), Check::Suspicious(_)));
+        // rate limited answers were never checked
+        assert_eq!(history.check(Part::Two, "7"), Check::Fine);
+
+        let solved = History::parse("2 correct = 42").unwrap();
+        assert!(matches!(solved.check(Part::Two, "43"), Check::Refused(_)));
+    }
+
+    #[test]
+    fn submits_and_records_attempts() {
+        let server = MockServer::start(|request| {
+            let text = match request.body.as_str() {
+                "level=1&answer=150" => "That's the right answer!",
+                "level=1&answer=1000" => "That's not the right answer; your answer is too high.",
+                _ => "That's not the right answer.",
+            };
+            Response::new(200, &page(text))
+        }).unwrap();
+
+        let root = std::env::temp_dir().join(format!("aoc_submit_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let config = Config {
+            session: Some(String::from("secret")),
+            base_url: server.url(),
+            min_interval: Duration::ZERO,
+            ..Config::default()
+        };
+
+        assert_eq!(submit(&config, &root, 2, Part::One, "1000").unwrap().outcome, Outcome::TooHigh);
+        // known to be wrong, so this never reaches the server
+        assert!(submit(&config, &root, 2, Part::One, "1000").is_err());
+        assert_eq!(submit(&config, &root, 2, Part::One, "150").unwrap().outcome, Outcome::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/2021/day/2/answer");
+        assert_eq!(requests[0].header("cookie"), Some("session=secret"));
+
+        let history = History::load(&History::path_for(&root, 2)).unwrap();
+        assert_eq!(history.attempts.iter().map(|a| a.outcome).collect::<Vec<_>>(), vec![Outcome::TooHigh, Outcome::Correct]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
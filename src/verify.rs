@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::expected::{self, Dataset, ExpectedAnswers};
+use crate::log::{error, warning};
+use crate::runner::{self, RunResult};
+use crate::solution::{AnySolution, Answer, Part};
+
+/// How a computed answer compares to the recorded one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// computed answer equals the recorded one
+    Pass,
+    /// the solution couldn't produce an answer (bad input, panic, ...)
+    Fail(String),
+    /// nothing was recorded for this day / part / input
+    Missing,
+    /// the solution now computes something different than what was recorded
+    Regression { expected: Answer, actual: Answer },
+}
+
+impl Verdict {
+    /// `Missing` is only a warning, everything except `Pass` and `Missing` is an error
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Fail(_) | Self::Regression { .. })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Fail(_) => "FAIL",
+            Self::Missing => "MISSING",
+            Self::Regression { .. } => "REGRESSION",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fail(msg) => write!(f, "{}: {}", self.label(), msg),
+            Self::Regression { expected, actual } =>
+                write!(f, "{}: expected {}, got {}", self.label(), one_line(expected), one_line(actual)),
+            _ => write!(f, "{}", self.label()),
+        }
+    }
+}
+
+/// ASCII art doesn't fit into a single report line, so its rows are joined with `/`
+fn one_line(answer: &Answer) -> String {
+    match answer {
+        Answer::Art(rows) => rows.join("/"),
+        other => other.to_string(),
+    }
+}
+
+/// The verdict for one run
+#[derive(Debug)]
+pub struct Verification {
+    pub dataset: Dataset,
+    pub result: RunResult,
+    pub verdict: Verdict,
+}
+
+/// Compares a single run to what was recorded
+pub fn judge(result: &RunResult, expected: Option<&Answer>) -> Verdict {
+    match (&result.outcome, expected) {
+        (Err(msg), _) => Verdict::Fail(msg.clone()),
+        (Ok(_), None) => Verdict::Missing,
+        (Ok(actual), Some(expected)) if expected::answers_match(expected, actual) => Verdict::Pass,
+        (Ok(actual), Some(expected)) => Verdict::Regression {
+            expected: expected.clone(),
+            actual: actual.clone(),
+        },
+    }
+}
+
+/// Runs every `part` of every solution on the given `datasets` and compares the results
+/// with the recorded answers. If `record` is set, answers which are missing are written
+/// to the answers file (recorded answers are never overwritten)
+pub fn verify(solutions: &[&dyn AnySolution], parts: &[Part], datasets: &[Dataset], record: bool) -> Vec<Verification> {
+    let mut verifications = Vec::new();
+
+    for solution in solutions {
+        let mut answers = match ExpectedAnswers::load(solution.day()) {
+            Ok(answers) => answers,
+            Err(msg) => {
+                warning!("{}", msg);
+                ExpectedAnswers::default()
+            }
+        };
+        let mut changed = false;
+
+        for dataset in datasets {
+            for result in runner::run_day(*solution, parts, &dataset.input_kind(), true) {
+                let verdict = judge(&result, answers.get(*dataset, result.part));
+
+                if record && verdict == Verdict::Missing {
+                    if let Ok(answer) = &result.outcome {
+                        answers.insert(*dataset, result.part, answer.clone());
+                        changed = true;
+                    }
+                }
+
+                verifications.push(Verification { dataset: *dataset, result, verdict });
+            }
+        }
+
+        if changed {
+            if let Err(err) = answers.save(solution.day()) {
+                error!("Couldn't record answers of day {}: {}", solution.day(), err);
+            }
+        }
+    }
+
+    verifications
+}
+
+/// Prints one line per verification and a summary
+pub fn print_report(verifications: &[Verification]) {
+    println!("{:>4} | {:>4} | {:<5} | Verdict", "Day", "Part", "Input");
+    println!("{}", "-".repeat(40));
+    for verification in verifications {
+        println!("{:>4} | {:>4} | {:<5} | {}",
+                 verification.result.day,
+                 verification.result.part,
+                 verification.dataset.name(),
+                 verification.verdict);
+    }
+
+    let count = |label: &str| verifications.iter().filter(|v| v.verdict.label() == label).count();
+    println!("\n{} passed, {} failed, {} missing, {} regressions",
+             count("PASS"),
+             count("FAIL"),
+             count("MISSING"),
+             count("REGRESSION"));
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::log::{error, info};
+use crate::output;
+use crate::runner::{InputKind, RunResult};
+use crate::solution::{Answer, Part};
+
+/// When each file below a directory was modified last
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+/// Everything `watch` needs to know
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchConfig {
+    pub day: u8,
+    pub parts: Vec<Part>,
+    pub input: InputKind,
+    /// how often the files are looked at
+    pub interval: Duration,
+    /// build with `--release` (timings mean more, building takes longer)
+    pub release: bool,
+}
+
+/// Collects the modification times of all files below `dir`. Unreadable files are left out
+pub fn snapshot(dir: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            match entry.metadata() {
+                Ok(meta) if meta.is_dir() => pending.push(path),
+                Ok(meta) => {
+                    if let Ok(modified) = meta.modified() {
+                        files.insert(path, modified);
+                    }
+                },
+                Err(_) => {},
+            }
+        }
+    }
+    files
+}
+
+/// Files which were added, removed or modified between `before` and `after`
+pub fn changed(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .collect();
+    paths.extend(before.keys().filter(|path| !after.contains_key(*path)).cloned());
+    paths.sort();
+    paths
+}
+
+/// One part of the latest run next to the run before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartDiff {
+    pub part: Part,
+    pub outcome: Result<Answer, String>,
+    pub elapsed: Duration,
+    /// `None` on the first run (or if the part didn't run before)
+    pub previous: Option<(Result<Answer, String>, Duration)>,
+}
+
+impl PartDiff {
+    pub fn answer_changed(&self) -> bool {
+        self.previous.as_ref().is_some_and(|(outcome, _)| *outcome != self.outcome)
+    }
+
+    /// How much slower (positive) or faster (negative) the part got, in percent
+    pub fn time_change(&self) -> Option<f64> {
+        let (_, before) = self.previous.as_ref()?;
+        if before.is_zero() {
+            return None;
+        }
+        Some((self.elapsed.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+/// Pairs every result of `current` with the result of the same part in `previous`
+pub fn diff(previous: &[RunResult], current: &[RunResult]) -> Vec<PartDiff> {
+    current
+        .iter()
+        .map(|result| PartDiff {
+            part: result.part,
+            outcome: result.outcome.clone(),
+            elapsed: result.elapsed,
+            previous: previous
+                .iter()
+                .find(|before| before.part == result.part)
+                .map(|before| (before.outcome.clone(), before.elapsed)),
+        })
+        .collect()
+}
+
+/// ASCII art doesn't fit into a table cell, so its rows are joined with `/`
+fn cell(outcome: &Result<Answer, String>) -> String {
+    match outcome {
+        Ok(Answer::Art(rows)) => rows.join("/"),
+        Ok(answer) => answer.to_string(),
+        Err(msg) => format!("FAILED: {}", msg),
+    }
+}
+
+pub fn print_diff(diffs: &[PartDiff]) {
+    println!("{:>4} | {:<20} | {:<20} | {:>12} | {:>8}", "Part", "Answer", "Before", "Time", "Change");
+    println!("{}", "-".repeat(78));
+    for diff in diffs {
+        let before = match &diff.previous {
+            None => String::new(),
+            Some(_) if !diff.answer_changed() => String::from("(same)"),
+            Some((outcome, _)) => cell(outcome),
+        };
+        let change = diff.time_change().map(|percent| format!("{:+.1}%", percent)).unwrap_or_default();
+        println!("{:>4} | {:<20} | {:<20} | {:>12} | {:>8}",
+                 diff.part,
+                 cell(&diff.outcome),
+                 before,
+                 format!("{:.3?}", diff.elapsed),
+                 change);
+    }
+}
+
+/// Rebuilds the crate and runs the day through `cargo run` (this process can't reload its own code).
+/// The results are read back from the JSON output; compile errors show up on stderr
+fn run_with_cargo(config: &WatchConfig) -> Result<Vec<RunResult>, String> {
+    let cargo = env::var("CARGO").unwrap_or(String::from("cargo"));
+    let mut command = Command::new(cargo);
+    command.args(["run", "--quiet"]);
+    if config.release {
+        command.arg("--release");
+    }
+    command.args(["--", "run", "--day", &config.day.to_string(), "--input", &config.input.to_string(), "--format", "json"]);
+    if let [part] = config.parts.as_slice() {
+        command.args(["--part", &part.to_string()]);
+    }
+
+    let output = command
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| format!("Couldn't start cargo: {}", err))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let results = stdout
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(output::from_json)
+        .collect::<Result<Vec<RunResult>, String>>()?;
+    // `run` fails for failed parts as well, but then it still prints them
+    if results.is_empty() {
+        return Err(String::from("cargo run failed (see above)"));
+    }
+    Ok(results)
+}
+
+/// Waits until something below `dir` changes and the files stay put for one `interval`
+/// (editors often write in several steps). Returns the new snapshot and what changed
+fn wait_for_change(dir: &Path, seen: &Snapshot, interval: Duration) -> (Snapshot, Vec<PathBuf>) {
+    loop {
+        thread::sleep(interval);
+        let mut now = snapshot(dir);
+        if now == *seen {
+            continue;
+        }
+
+        loop {
+            thread::sleep(interval);
+            let settled = snapshot(dir);
+            if settled == now {
+                break;
+            }
+            now = settled;
+        }
+        let paths = changed(seen, &now);
+        return (now, paths);
+    }
+}
+
+/// Runs the day, then again every time a file in `src/dayN/` changes, printing how answers
+/// and timings differ from the run before. Only returns if the day doesn't exist
+pub fn watch(config: &WatchConfig) -> Result<(), String> {
+    let dir = PathBuf::from(format!("./src/day{}", config.day));
+    if !dir.is_dir() {
+        return Err(format!("{} does not exist", dir.display()));
+    }
+
+    let mut seen = snapshot(&dir);
+    let mut previous: Vec<RunResult> = Vec::new();
+    info!("Watching {} (stop with Ctrl-C)", dir.display());
+    loop {
+        info!("Running day {}", config.day);
+        match run_with_cargo(config) {
+            Ok(results) => {
+                print_diff(&diff(&previous, &results));
+                previous = results;
+            },
+            Err(msg) => error!("{}", msg),
+        }
+
+        let (now, paths) = wait_for_change(&dir, &seen, config.interval);
+        seen = now;
+        let names: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+        info!("Changed: {}", names.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn result(part: Part, answer: u64, millis: u64) -> RunResult {
+        RunResult {
+            day: 11,
+            part,
+            input: InputKind::Test,
+            outcome: Ok(Answer::Integer(answer)),
+            elapsed: Duration::from_millis(millis),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn notices_added_modified_and_removed_files() {
+        let dir = env::temp_dir().join(format!("aoc_watch_{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("mod.rs"), "a").unwrap();
+        fs::write(dir.join("input.txt"), "1").unwrap();
+        let before = snapshot(&dir);
+        assert_eq!(before.len(), 2);
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options().write(true).open(dir.join("mod.rs")).unwrap().set_modified(later).unwrap();
+        fs::remove_file(dir.join("input.txt")).unwrap();
+        fs::write(dir.join("sub/testinput.txt"), "2").unwrap();
+
+        assert_eq!(changed(&before, &snapshot(&dir)),
+                   vec![dir.join("input.txt"), dir.join("mod.rs"), dir.join("sub/testinput.txt")]);
+        assert!(changed(&before, &before).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compares_answers_and_timings() {
+        let first = diff(&[], &[result(Part::One, 1656, 10)]);
+        assert_eq!(first[0].previous, None);
+        assert!(!first[0].answer_changed());
+
+        let previous = [result(Part::One, 1656, 10), result(Part::Two, 195, 20)];
+        let diffs = diff(&previous, &[result(Part::One, 1656, 5), result(Part::Two, 196, 30)]);
+        assert!(!diffs[0].answer_changed());
+        assert_eq!(diffs[0].time_change(), Some(-50.0));
+        assert!(diffs[1].answer_changed());
+        assert_eq!(diffs[1].previous, Some((Ok(Answer::Integer(195)), Duration::from_millis(20))));
+        assert_eq!(diffs[1].time_change(), Some(50.0));
+    }
+}
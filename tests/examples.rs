@@ -0,0 +1,117 @@
+//! Runs every day on its `testinput.txt` and checks the example answers given in the riddles
+
+use std::path::PathBuf;
+
+use advent_of_code_2021::loader::{self, LoadError};
+use advent_of_code_2021::registry;
+use advent_of_code_2021::solution::{Answer, Part};
+
+/// Runs `part` of `day` on `src/dayXY/testinput.txt`
+fn example(day: u8, part: Part) -> Answer {
+    let solution = registry::find(day).expect("day is not registered");
+    let path = PathBuf::from(format!("src/day{}/testinput.txt", day));
+    let input = loader::load_file(&path, true).expect("couldn't load the test input");
+
+    solution.run(input.lines(), part).expect("couldn't solve the test input")
+}
+
+fn assert_example(day: u8, part1: u64, part2: u64) {
+    assert_eq!(example(day, Part::One), Answer::Integer(part1), "day {} part 1", day);
+    assert_eq!(example(day, Part::Two), Answer::Integer(part2), "day {} part 2", day);
+}
+
+#[test]
+fn every_registered_day_has_a_test_input() {
+    for solution in registry::all() {
+        let path = PathBuf::from(format!("src/day{}/testinput.txt", solution.day()));
+        let input = match loader::load_file(&path, true) {
+            Ok(input) => input,
+            // a day fresh from `aoc new-day`, the example isn't copied over yet
+            Err(LoadError::Empty(_)) => continue,
+            Err(err) => panic!("couldn't load the test input: {}", err),
+        };
+        assert!(solution.parse_any(input.lines()).is_ok(), "day {} can't parse its test input", solution.day());
+    }
+}
+
+#[test]
+fn day1_sonar_sweep() {
+    assert_example(1, 7, 5);
+}
+
+#[test]
+fn day2_dive() {
+    assert_example(2, 150, 900);
+}
+
+#[test]
+fn day3_binary_diagnostic() {
+    assert_example(3, 198, 230);
+}
+
+#[test]
+fn day4_giant_squid() {
+    assert_example(4, 4512, 1924);
+}
+
+#[test]
+fn day5_hydrothermal_venture() {
+    assert_example(5, 5, 12);
+}
+
+#[test]
+fn day6_lanternfish() {
+    assert_example(6, 5934, 26984457539);
+}
+
+#[test]
+fn day7_the_treachery_of_whales() {
+    assert_example(7, 37, 168);
+}
+
+#[test]
+fn day8_seven_segment_search() {
+    assert_example(8, 26, 61229);
+}
+
+#[test]
+fn day9_smoke_basin() {
+    assert_example(9, 15, 1134);
+}
+
+#[test]
+fn day10_syntax_scoring() {
+    assert_example(10, 26397, 288957);
+}
+
+#[test]
+fn day11_dumbo_octopus() {
+    assert_example(11, 1656, 195);
+}
+
+#[test]
+fn day12_passage_pathing() {
+    assert_example(12, 10, 36);
+}
+
+#[test]
+fn day13_transparent_origami() {
+    assert_eq!(example(13, Part::One), Answer::Integer(17));
+    assert_eq!(example(13, Part::Two), Answer::Art(vec![
+        String::from("#####"),
+        String::from("#   #"),
+        String::from("#   #"),
+        String::from("#   #"),
+        String::from("#####"),
+    ]));
+}
+
+#[test]
+fn day14_extended_polymerization_part1() {
+    assert_eq!(example(14, Part::One), Answer::Integer(1588));
+}
+
+#[test]
+fn day14_extended_polymerization_part2() {
+    assert_eq!(example(14, Part::Two), Answer::Integer(2188189693529));
+}